@@ -1,130 +1,424 @@
-use std::collections::HashSet;
-
-fn parse_calories(file: &str) -> Vec<HashSet<u32>> {
-    let (mut vec, set) = file
-        .lines()
-        .map(|line| {
-            if line.is_empty() {
-                None
-            } else {
-                line.parse().ok()
+pub mod query;
+pub mod rebalance;
+pub mod stats;
+
+use alloc::collections::BinaryHeap;
+use alloc::vec::Vec;
+use alloc::string::{String, ToString};
+use core::cmp::Reverse;
+use core::fmt;
+use core::mem;
+use core::str::FromStr;
+use crate::normalize;
+
+/// Unsigned integer types calories can be counted in. Pick a wider one than
+/// `u32` for inputs whose totals might not fit.
+pub trait Calories: Copy + Ord + Default + FromStr + fmt::Debug + fmt::Display {
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn as_f64(self) -> f64;
+    fn as_u128(self) -> u128;
+    /// Truncates values that do not fit
+    fn from_u128(value: u128) -> Self;
+}
+
+macro_rules! impl_calories {
+    ($($t:ty),*) => {
+        $(
+            impl Calories for $t {
+                fn checked_add(self, rhs: Self) -> Option<Self> { <$t>::checked_add(self, rhs) }
+                fn as_f64(self) -> f64 { self as f64 }
+                fn as_u128(self) -> u128 { self as u128 }
+                fn from_u128(value: u128) -> Self { value as $t }
             }
-        })
-        .fold((Vec::new(), HashSet::new()), |(mut vec, mut set), value| {
-            match value {
-                Some(calories) => {
-                    set.insert(calories);
-                    (vec, set)
-                }
-                None => {
-                    vec.push(set);
-                    (vec, HashSet::new())
-                }
+        )*
+    };
+}
+
+impl_calories!(u8, u16, u32, u64, u128);
+
+/// A sum of calories did not fit the integer type it was counted in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overflow;
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "calorie total overflowed, use a wider integer type")
+    }
+}
+
+pub fn checked_sum<T: Calories>(values: impl IntoIterator<Item=T>) -> Result<T, Overflow> {
+    values
+        .into_iter()
+        .try_fold(T::default(), |sum, value| sum.checked_add(value).ok_or(Overflow))
+}
+
+/// Snacks carried by one elf, in the order they were listed. The same
+/// calorie value can appear several times.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Elf<T = u32>(Vec<T>);
+
+impl<T: Calories> Elf<T> {
+    pub fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    pub fn push(&mut self, calories: T) {
+        self.0.push(calories);
+    }
+
+    pub fn items(&self) -> &[T] {
+        &self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn total(&self) -> Result<T, Overflow> {
+        checked_sum(self.0.iter().copied())
+    }
+}
+
+impl<T> From<Vec<T>> for Elf<T> {
+    fn from(items: Vec<T>) -> Self {
+        Self(items)
+    }
+}
+
+impl<T> FromIterator<T> for Elf<T> {
+    fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+/// A line that is neither blank nor a calorie count. `line` is 1-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub content: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: not a calorie count: {:?}", self.line, self.content)
+    }
+}
+
+/// Elves are separated by blank lines, any number of them. Whitespace around
+/// a calorie count is ignored, anything else that does not parse is an error.
+pub fn parse_calories(file: &str) -> Result<Vec<Elf>, ParseError> {
+    parse_calories_as(file)
+}
+
+/// Like `parse_calories`, counting calories in `T`
+pub fn parse_calories_as<T: Calories>(file: &str) -> Result<Vec<Elf<T>>, ParseError> {
+    let mut elves = Vec::new();
+    let mut elf = Elf::new();
+
+    for (idx, line) in normalize::lines(file).enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            if !elf.is_empty() {
+                elves.push(mem::take(&mut elf));
             }
-        });
-    if !set.is_empty() {
-        vec.push(set);
+        } else {
+            let calories = line
+                .parse()
+                .map_err(|_| ParseError { line: idx + 1, content: line.to_string() })?;
+            elf.push(calories);
+        }
+    }
+    if !elf.is_empty() {
+        elves.push(elf);
     }
 
-    vec
+    Ok(elves)
 }
 
-fn most_calories(elves: &Vec<HashSet<u32>>) -> u32 {
-    elves
-        .iter()
-        .map(|calories| calories.iter().sum())
-        .max()
-        .unwrap()
+/// What `top_n` does with elves tied with the last elf that made the cut.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TiePolicy {
+    /// Exactly `n` elves, an earlier elf wins a tie against a later one
+    StableByIndex,
+    /// Every elf tied with the `n`th elf is included, so there can be more
+    /// than `n` elves
+    IncludeTies,
+}
+
+/// An elf picked by `top_n`, `index` being its position in the inventory list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ranked<T = u32> {
+    pub index: usize,
+    pub total: T,
 }
 
-fn top_three(elves: &Vec<HashSet<u32>>) -> u32 {
-    let mut elves = elves
+/// The `n` elves carrying the most calories, most first and ties in index
+/// order. Keeps a heap of at most `n` elves, so runs in O(elves log n).
+pub fn top_n<T: Calories>(elves: &[Elf<T>], n: usize, ties: TiePolicy) -> Result<Vec<Ranked<T>>, Overflow> {
+    if n == 0 {
+        return Ok(Vec::new());
+    }
+
+    let totals = elves
         .iter()
-        .map(|calories| calories.iter().sum())
-        .collect::<Vec<u32>>();
+        .map(Elf::total)
+        .collect::<Result<Vec<T>, Overflow>>()?;
+
+    // Min-heap of the best elves so far, the worst of them on top. A higher
+    // index ranks lower on equal totals.
+    let mut heap = BinaryHeap::with_capacity(n + 1);
+    for (index, &total) in totals.iter().enumerate() {
+        heap.push(Reverse((total, Reverse(index))));
+        if heap.len() > n {
+            heap.pop();
+        }
+    }
 
-    elves.sort_by(|a, b| b.cmp(&a));
+    let mut top: Vec<Ranked<T>> = heap
+        .into_iter()
+        .map(|Reverse((total, Reverse(index)))| Ranked { index, total })
+        .collect();
 
-    elves.iter().take(3).sum()
+    if ties == TiePolicy::IncludeTies && top.len() == n {
+        let cutoff = top.iter().map(|ranked| ranked.total).min().unwrap();
+        top.retain(|ranked| ranked.total > cutoff);
+        top.extend(
+            totals
+                .iter()
+                .enumerate()
+                .filter(|&(_, &total)| total == cutoff)
+                .map(|(index, &total)| Ranked { index, total })
+        );
+    }
+
+    top.sort_by(|a, b| b.total.cmp(&a.total).then(a.index.cmp(&b.index)));
+    Ok(top)
+}
+
+/// The largest total, `None` if there are no elves
+pub fn most_calories<T: Calories>(elves: &[Elf<T>]) -> Result<Option<T>, Overflow> {
+    Ok(top_n(elves, 1, TiePolicy::StableByIndex)?.first().map(|ranked| ranked.total))
+}
+
+pub fn top_three<T: Calories>(elves: &[Elf<T>]) -> Result<T, Overflow> {
+    checked_sum(
+        top_n(elves, 3, TiePolicy::StableByIndex)?
+            .iter()
+            .map(|ranked| ranked.total)
+    )
 }
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashSet;
-    use crate::day1::{most_calories, parse_calories, top_three};
+    use crate::day1::{Elf, most_calories, Overflow, parse_calories, parse_calories_as, ParseError, Ranked, TiePolicy, top_n, top_three};
 
     #[test]
     fn test_parse_example() {
         let example = include_str!("example.txt");
-        let calories = parse_calories(example);
+        let calories = parse_calories(example).unwrap();
         assert_eq!(
             calories,
             vec![
-                HashSet::from([
+                Elf::from(vec![
                     1000,
                     2000,
                     3000
                 ]),
-                HashSet::from([
+                Elf::from(vec![
                     4000
                 ]),
-                HashSet::from([
+                Elf::from(vec![
                     5000,
                     6000
                 ]),
-                HashSet::from([
+                Elf::from(vec![
                     7000,
                     8000,
                     9000
                 ]),
-                HashSet::from([
+                Elf::from(vec![
                     10000
                 ])
             ]
         )
     }
 
+    #[test]
+    fn test_parse_duplicate_snacks() {
+        let calories = parse_calories("3000\n1000\n3000\n\n5000\n").unwrap();
+
+        assert_eq!(
+            calories,
+            vec![
+                Elf::from(vec![3000, 1000, 3000]),
+                Elf::from(vec![5000])
+            ]
+        );
+        assert_eq!(calories[0].items(), &[3000, 1000, 3000]);
+    }
+
+    #[test]
+    fn test_duplicate_snacks_most_calories() {
+        let calories = parse_calories("1000\n1000\n\n1500\n").unwrap();
+
+        assert_eq!(
+            most_calories(&calories),
+            Ok(Some(2000))
+        )
+    }
+
+    #[test]
+    fn test_duplicate_snacks_top_three() {
+        let calories = parse_calories("1000\n1000\n\n1500\n\n700\n700\n700\n\n100").unwrap();
+
+        assert_eq!(
+            top_three(&calories),
+            Ok(5600)
+        )
+    }
+
     #[test]
     fn test_example_most_calories() {
         let example = include_str!("example.txt");
-        let calories = parse_calories(example);
+        let calories = parse_calories(example).unwrap();
 
         assert_eq!(
             most_calories(&calories),
-            24000
+            Ok(Some(24000))
         )
     }
 
     #[test]
     fn test_input_most_calories() {
         let input = include_str!("input.txt");
-        let calories = parse_calories(input);
+        let calories = parse_calories(input).unwrap();
 
         assert_eq!(
             most_calories(&calories),
-            68775
+            Ok(Some(68775))
         )
     }
 
+    #[test]
+    fn test_no_elves() {
+        let calories = parse_calories("").unwrap();
+
+        assert_eq!(
+            most_calories(&calories),
+            Ok(None)
+        );
+        assert_eq!(
+            top_three(&calories),
+            Ok(0)
+        );
+    }
+
     #[test]
     fn test_example_top_three() {
         let example = include_str!("example.txt");
-        let calories = parse_calories(example);
+        let calories = parse_calories(example).unwrap();
 
         assert_eq!(
             top_three(&calories),
-            45000
+            Ok(45000)
         )
     }
 
     #[test]
     fn test_input_top_three() {
         let input = include_str!("input.txt");
-        let calories = parse_calories(input);
+        let calories = parse_calories(input).unwrap();
 
         assert_eq!(
             top_three(&calories),
-            202585
+            Ok(202585)
         )
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_example_top_n() {
+        let example = include_str!("example.txt");
+        let calories = parse_calories(example).unwrap();
+
+        assert_eq!(
+            top_n(&calories, 2, TiePolicy::StableByIndex).unwrap(),
+            vec![
+                Ranked { index: 3, total: 24000 },
+                Ranked { index: 2, total: 11000 },
+            ]
+        );
+        assert_eq!(
+            top_n(&calories, 10, TiePolicy::StableByIndex).unwrap().len(),
+            5
+        );
+        assert!(top_n(&calories, 0, TiePolicy::IncludeTies).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_top_n_ties() {
+        let calories = parse_calories("500\n\n300\n\n200\n100\n\n300\n\n100").unwrap();
+
+        assert_eq!(
+            top_n(&calories, 2, TiePolicy::StableByIndex).unwrap(),
+            vec![
+                Ranked { index: 0, total: 500 },
+                Ranked { index: 1, total: 300 },
+            ]
+        );
+        assert_eq!(
+            top_n(&calories, 2, TiePolicy::IncludeTies).unwrap(),
+            vec![
+                Ranked { index: 0, total: 500 },
+                Ranked { index: 1, total: 300 },
+                Ranked { index: 2, total: 300 },
+                Ranked { index: 3, total: 300 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_overflow_is_an_error() {
+        let input = "4000000000\n300000000\n\n4000000000\n\n1";
+
+        assert_eq!(most_calories(&parse_calories(input).unwrap()), Err(Overflow));
+        assert_eq!(top_three(&parse_calories_as::<u64>(input).unwrap()), Ok(8300000001));
+        assert_eq!(top_three(&parse_calories_as::<u64>("18446744073709551615\n\n1").unwrap()), Err(Overflow));
+        assert_eq!(top_three(&parse_calories_as::<u128>("18446744073709551615\n\n1").unwrap()), Ok(18446744073709551616));
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed_lines() {
+        assert_eq!(
+            parse_calories("1000\n12a\n2000"),
+            Err(ParseError { line: 2, content: "12a".to_string() })
+        );
+        assert_eq!(
+            parse_calories("1000\n\n-5").unwrap_err().to_string(),
+            "line 3: not a calorie count: \"-5\""
+        );
+    }
+
+    #[test]
+    fn test_parse_lone_cr_line_endings() {
+        assert_eq!(
+            parse_calories("1000\r2000\r\r3000"),
+            Ok(vec![
+                Elf::from(vec![1000, 2000]),
+                Elf::from(vec![3000])
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_whitespace_and_blank_runs() {
+        let calories = parse_calories("\n  1000 \n\t2000\n\n  \n\n3000\n\n\n").unwrap();
+
+        assert_eq!(
+            calories,
+            vec![
+                Elf::from(vec![1000, 2000]),
+                Elf::from(vec![3000])
+            ]
+        );
+    }
+}
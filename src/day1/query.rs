@@ -0,0 +1,104 @@
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use crate::day1::{Calories, Elf, Overflow, TiePolicy, top_n};
+
+/// Questions about a list of elf inventories. Elves are referred to by their
+/// index in the list.
+#[derive(Debug, Clone, Copy)]
+pub struct Query<'a, T = u32> {
+    elves: &'a [Elf<T>],
+}
+
+impl<'a, T: Calories> Query<'a, T> {
+    pub fn new(elves: &'a [Elf<T>]) -> Self {
+        Self { elves }
+    }
+
+    /// Elves whose total is strictly more than `calories`
+    pub fn carrying_more_than(&self, calories: T) -> Result<Vec<usize>, Overflow> {
+        let mut found = Vec::new();
+        for (index, elf) in self.elves.iter().enumerate() {
+            if elf.total()? > calories {
+                found.push(index);
+            }
+        }
+        Ok(found)
+    }
+
+    /// Elves with at least one snack of strictly more than `calories`
+    pub fn with_snack_heavier_than(&self, calories: T) -> Vec<usize> {
+        self.elves
+            .iter()
+            .enumerate()
+            .filter(|(_, elf)| elf.items().iter().any(|&item| item > calories))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Elves keyed by how many snacks they carry
+    pub fn grouped_by_snack_count(&self) -> BTreeMap<usize, Vec<usize>> {
+        let mut groups: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+        for (index, elf) in self.elves.iter().enumerate() {
+            groups.entry(elf.items().len()).or_default().push(index);
+        }
+        groups
+    }
+
+    /// Fewest elves whose combined total is strictly more than `calories`,
+    /// heaviest first, or `None` if even all of them together are not enough.
+    /// Taking the heaviest elves first is optimal for the number of elves.
+    pub fn fewest_exceeding(&self, calories: T) -> Result<Option<Vec<usize>>, Overflow> {
+        let mut combined = T::default();
+        let mut picked = Vec::new();
+
+        for ranked in top_n(self.elves, self.elves.len(), TiePolicy::StableByIndex)? {
+            if combined > calories {
+                break;
+            }
+            picked.push(ranked.index);
+            match combined.checked_add(ranked.total) {
+                Some(sum) => combined = sum,
+                // Past anything a `T` can hold, so past `calories` too
+                None => return Ok(Some(picked)),
+            }
+        }
+
+        Ok(if combined > calories { Some(picked) } else { None })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::BTreeMap;
+    use crate::day1::parse_calories;
+    use crate::day1::query::Query;
+
+    #[test]
+    fn test_example_queries() {
+        let example = include_str!("example.txt");
+        let elves = parse_calories(example).unwrap();
+        let query = Query::new(&elves);
+
+        assert_eq!(query.carrying_more_than(10000), Ok(vec![2, 3]));
+        assert_eq!(query.with_snack_heavier_than(8000), vec![3, 4]);
+        assert_eq!(
+            query.grouped_by_snack_count(),
+            BTreeMap::from([(1, vec![1, 4]), (2, vec![2]), (3, vec![0, 3])])
+        );
+    }
+
+    #[test]
+    fn test_fewest_exceeding() {
+        let example = include_str!("example.txt");
+        let elves = parse_calories(example).unwrap();
+        let query = Query::new(&elves);
+
+        assert_eq!(query.fewest_exceeding(0), Ok(Some(vec![3])));
+        assert_eq!(query.fewest_exceeding(24000), Ok(Some(vec![3, 2])));
+        assert_eq!(query.fewest_exceeding(45000), Ok(Some(vec![3, 2, 4, 0])));
+        assert_eq!(query.fewest_exceeding(55000), Ok(None));
+
+        let big = parse_calories("4000000000\n\n4000000000").unwrap();
+        assert_eq!(Query::new(&big).fewest_exceeding(4100000000), Ok(Some(vec![0, 1])));
+    }
+}
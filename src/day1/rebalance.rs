@@ -0,0 +1,325 @@
+use alloc::collections::BinaryHeap;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Reverse;
+use crate::cancel::{CancellationToken, Cancelled};
+use crate::day1::{Calories, Elf};
+
+/// A snack and where it was in the original inventories.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Snack<T = u32> {
+    pub elf: usize,
+    pub item: usize,
+    pub calories: T,
+}
+
+/// `snack` has to be handed over to elf `to`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Move<T = u32> {
+    pub snack: Snack<T>,
+    pub to: usize,
+}
+
+/// A redistribution of all snacks among the same elves. Loads are summed in
+/// `u128`, which holds any total of up to `u64` snacks and saturates at
+/// `u128::MAX` for `u128` snacks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plan<T = u32> {
+    carried: Vec<Vec<Snack<T>>>,
+}
+
+impl<T: Calories> Plan<T> {
+    /// Give each load to the elf already owning most of its snacks, so that
+    /// as few snacks as possible need to change hands.
+    fn new(loads: Vec<Vec<Snack<T>>>) -> Self {
+        let elves = loads.len();
+        let mut overlaps = vec![];
+        for (load, snacks) in loads.iter().enumerate() {
+            let mut owned = vec![0usize; elves];
+            for snack in snacks {
+                owned[snack.elf] += 1;
+            }
+            overlaps.extend(
+                owned
+                    .into_iter()
+                    .enumerate()
+                    .filter(|&(_, count)| count > 0)
+                    .map(|(elf, count)| (Reverse(count), load, elf))
+            );
+        }
+        overlaps.sort();
+
+        let mut owner = vec![None; elves];
+        let mut taken = vec![false; elves];
+        for (_, load, elf) in overlaps {
+            if owner[load].is_none() && !taken[elf] {
+                owner[load] = Some(elf);
+                taken[elf] = true;
+            }
+        }
+        let mut free = (0..elves).filter(|&elf| !taken[elf]);
+        let mut carried = vec![vec![]; elves];
+        for (load, snacks) in loads.into_iter().enumerate() {
+            let elf = owner[load].unwrap_or_else(|| free.next().unwrap());
+            carried[elf] = snacks;
+        }
+
+        Self { carried }
+    }
+
+    pub fn carried(&self, elf: usize) -> &[Snack<T>] {
+        &self.carried[elf]
+    }
+
+    /// Calories each elf ends up carrying
+    pub fn loads(&self) -> Vec<u128> {
+        self.carried
+            .iter()
+            .map(|snacks| total(snacks.iter().map(|snack| snack.calories)))
+            .collect()
+    }
+
+    pub fn max_load(&self) -> u128 {
+        self.loads().into_iter().max().unwrap_or(0)
+    }
+
+    /// Snacks ending up with a different elf than they started with
+    pub fn moves(&self) -> Vec<Move<T>> {
+        let mut moves: Vec<Move<T>> = self.carried
+            .iter()
+            .enumerate()
+            .flat_map(|(to, snacks)| snacks
+                .iter()
+                .filter(move |snack| snack.elf != to)
+                .map(move |&snack| Move { snack, to }))
+            .collect();
+        moves.sort_by_key(|m| (m.snack.elf, m.snack.item));
+        moves
+    }
+
+    /// The new inventories, snacks an elf kept first in their original order
+    pub fn elves(&self) -> Vec<Elf<T>> {
+        self.carried
+            .iter()
+            .map(|snacks| {
+                let mut snacks = snacks.clone();
+                snacks.sort_by_key(|snack| (snack.elf, snack.item));
+                snacks.into_iter().map(|snack| snack.calories).collect()
+            })
+            .collect()
+    }
+}
+
+/// Sum of `calories` in `u128`, saturating rather than overflowing
+fn total<T: Calories>(calories: impl IntoIterator<Item=T>) -> u128 {
+    calories
+        .into_iter()
+        .fold(0u128, |sum, calories| sum.saturating_add(calories.as_u128()))
+}
+
+/// Every snack, the largest first
+fn snacks<T: Calories>(elves: &[Elf<T>]) -> Vec<Snack<T>> {
+    let mut snacks: Vec<Snack<T>> = elves
+        .iter()
+        .enumerate()
+        .flat_map(|(elf, inventory)| inventory
+            .items()
+            .iter()
+            .enumerate()
+            .map(move |(item, &calories)| Snack { elf, item, calories }))
+        .collect();
+    snacks.sort_by_key(|snack| Reverse(snack.calories));
+    snacks
+}
+
+/// No plan can do better than the largest snack or an even split.
+pub fn lower_bound<T: Calories>(elves: &[Elf<T>]) -> u128 {
+    let snacks = elves.iter().flat_map(|elf| elf.items().iter().copied());
+    let total = total(snacks.clone());
+    let largest = snacks.max().unwrap_or_default().as_u128();
+    largest.max(total.div_ceil(elves.len().max(1) as u128))
+}
+
+/// Longest processing time first: hand out the snacks largest first, always to
+/// the least loaded elf. Runs in O(snacks log snacks) and its heaviest load is
+/// at most `lpt_ratio` times the optimal one.
+pub fn lpt<T: Calories>(elves: &[Elf<T>]) -> Plan<T> {
+    let mut loads = vec![vec![]; elves.len()];
+    let mut lightest: BinaryHeap<Reverse<(u128, usize)>> = (0..elves.len())
+        .map(|elf| Reverse((0, elf)))
+        .collect();
+
+    for snack in snacks(elves) {
+        let Reverse((load, elf)) = lightest.pop().unwrap();
+        loads[elf].push(snack);
+        lightest.push(Reverse((load.saturating_add(snack.calories.as_u128()), elf)));
+    }
+
+    Plan::new(loads)
+}
+
+/// Graham's bound for LPT on `elves` elves: 4/3 - 1/(3 * elves)
+pub fn lpt_ratio(elves: usize) -> f64 {
+    let elves = elves.max(1) as f64;
+    4.0 / 3.0 - 1.0 / (3.0 * elves)
+}
+
+struct Search<'a, T> {
+    snacks: &'a [Snack<T>],
+    remaining: Vec<u128>,
+    loads: Vec<u128>,
+    assigned: Vec<usize>,
+    best: u128,
+    best_assigned: Option<Vec<usize>>,
+    lower_bound: u128,
+    cancel: &'a CancellationToken,
+    nodes: u64,
+}
+
+impl<T: Calories> Search<'_, T> {
+    fn branch(&mut self, next: usize) -> Result<(), Cancelled> {
+        if self.nodes.is_multiple_of(4096) {
+            self.cancel.check()?;
+        }
+        self.nodes += 1;
+
+        if next == self.snacks.len() {
+            self.best = *self.loads.iter().max().unwrap();
+            self.best_assigned = Some(self.assigned.clone());
+            return Ok(());
+        }
+
+        let elves = self.loads.len() as u128;
+        let placed: u128 = self.loads.iter().sum();
+        if (placed + self.remaining[next]).div_ceil(elves) >= self.best {
+            return Ok(());
+        }
+
+        let calories = self.snacks[next].calories.as_u128();
+        for elf in 0..self.loads.len() {
+            let load = self.loads[elf];
+            // Elves with equal loads are interchangeable, try only the first
+            if load + calories >= self.best || self.loads[..elf].contains(&load) {
+                continue;
+            }
+            self.loads[elf] += calories;
+            self.assigned.push(elf);
+            self.branch(next + 1)?;
+            self.assigned.pop();
+            self.loads[elf] -= calories;
+
+            if self.best == self.lower_bound {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Optimal plan by branch and bound, starting from the LPT plan and pruning
+/// anything that cannot beat the best plan so far. Exponential in the worst
+/// case, so it checks `cancel` as it goes. Snacks totalling more than
+/// `u128::MAX` cannot be searched and get the LPT plan.
+pub fn exact<T: Calories>(elves: &[Elf<T>], cancel: &CancellationToken) -> Result<Plan<T>, Cancelled> {
+    let heuristic = lpt(elves);
+    let lower_bound = lower_bound(elves);
+    if heuristic.max_load() <= lower_bound {
+        return Ok(heuristic);
+    }
+
+    let snacks = snacks(elves);
+    let mut remaining = vec![0u128; snacks.len() + 1];
+    for i in (0..snacks.len()).rev() {
+        match remaining[i + 1].checked_add(snacks[i].calories.as_u128()) {
+            Some(sum) => remaining[i] = sum,
+            None => return Ok(heuristic),
+        }
+    }
+
+    let mut search = Search {
+        snacks: &snacks,
+        remaining,
+        loads: vec![0; elves.len()],
+        assigned: Vec::with_capacity(snacks.len()),
+        best: heuristic.max_load(),
+        best_assigned: None,
+        lower_bound,
+        cancel,
+        nodes: 0,
+    };
+    search.branch(0)?;
+
+    match search.best_assigned {
+        None => Ok(heuristic),
+        Some(assigned) => {
+            let mut loads = vec![vec![]; elves.len()];
+            for (snack, elf) in snacks.iter().zip(assigned) {
+                loads[elf].push(*snack);
+            }
+            Ok(Plan::new(loads))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cancel::{CancellationToken, Cancelled};
+    use crate::day1::{parse_calories, parse_calories_as, Elf};
+    use crate::day1::rebalance::{exact, lower_bound, lpt, lpt_ratio, Move, Snack};
+
+    #[test]
+    fn test_example_is_balanced_perfectly() {
+        let example = include_str!("example.txt");
+        let elves = parse_calories(example).unwrap();
+
+        assert_eq!(lower_bound(&elves), 11000);
+        assert_eq!(lpt(&elves).max_load(), 11000);
+        assert_eq!(exact(&elves, &CancellationToken::new()).unwrap().loads(), vec![11000; 5]);
+    }
+
+    #[test]
+    fn test_exact_beats_lpt() {
+        let elves = parse_calories("3\n3\n2\n\n2\n2").unwrap();
+        let heuristic = lpt(&elves);
+        let optimal = exact(&elves, &CancellationToken::new()).unwrap();
+
+        assert_eq!(heuristic.max_load(), 7);
+        assert_eq!(optimal.max_load(), 6);
+        assert!(heuristic.max_load() as f64 <= optimal.max_load() as f64 * lpt_ratio(2) + 1e-9);
+        assert_eq!(
+            optimal.moves(),
+            vec![Move { snack: Snack { elf: 0, item: 2, calories: 2 }, to: 1 }]
+        );
+        assert_eq!(
+            optimal.elves(),
+            parse_calories("3\n3\n\n2\n2\n2").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_wide_calories() {
+        let elves = parse_calories_as::<u64>("5000000000\n3000000000\n\n2000000000").unwrap();
+        let optimal = exact(&elves, &CancellationToken::new()).unwrap();
+
+        assert_eq!(lower_bound(&elves), 5_000_000_000);
+        assert_eq!(optimal.loads(), vec![5_000_000_000, 5_000_000_000]);
+        assert_eq!(
+            optimal.moves(),
+            vec![Move { snack: Snack { elf: 0, item: 1, calories: 3_000_000_000u64 }, to: 1 }]
+        );
+
+        let huge = vec![Elf::from(vec![u128::MAX, u128::MAX]), Elf::from(vec![1u128])];
+        assert_eq!(lower_bound(&huge), u128::MAX);
+        assert_eq!(exact(&huge, &CancellationToken::new()).unwrap().max_load(), u128::MAX);
+    }
+
+    #[test]
+    fn test_exact_is_cancellable() {
+        let elves = parse_calories("3\n3\n2\n\n2\n2").unwrap();
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+
+        assert_eq!(exact(&elves, &cancel), Err(Cancelled));
+    }
+}
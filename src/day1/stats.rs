@@ -0,0 +1,227 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::fmt::Write;
+use crate::day1::{Calories, Elf, Overflow};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatsError {
+    NoElves,
+    Overflow,
+}
+
+impl From<Overflow> for StatsError {
+    fn from(_: Overflow) -> Self {
+        StatsError::Overflow
+    }
+}
+
+impl fmt::Display for StatsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StatsError::NoElves => write!(f, "no elves to describe"),
+            StatsError::Overflow => write!(f, "{}", Overflow),
+        }
+    }
+}
+
+/// Distribution of calorie totals and snack counts over a list of elves.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalorieStats<T = u32> {
+    pub elves: usize,
+    pub min: T,
+    pub max: T,
+    pub mean: f64,
+    pub median: f64,
+    pub std_dev: f64,
+    sorted_totals: Vec<T>,
+    item_counts: Vec<usize>,
+}
+
+impl<T: Calories> CalorieStats<T> {
+    pub fn new(elves: &[Elf<T>]) -> Result<Self, StatsError> {
+        if elves.is_empty() {
+            return Err(StatsError::NoElves);
+        }
+
+        let mut sorted_totals = elves
+            .iter()
+            .map(Elf::total)
+            .collect::<Result<Vec<T>, Overflow>>()?;
+        sorted_totals.sort_unstable();
+
+        let n = sorted_totals.len();
+        let mean = sorted_totals.iter().map(|total| total.as_f64()).sum::<f64>() / n as f64;
+        let median = if n.is_multiple_of(2) {
+            (sorted_totals[n / 2 - 1].as_f64() + sorted_totals[n / 2].as_f64()) / 2.0
+        } else {
+            sorted_totals[n / 2].as_f64()
+        };
+        let variance = sorted_totals
+            .iter()
+            .map(|total| (total.as_f64() - mean) * (total.as_f64() - mean))
+            .sum::<f64>() / n as f64;
+
+        Ok(Self {
+            elves: n,
+            min: sorted_totals[0],
+            max: sorted_totals[n - 1],
+            mean,
+            median,
+            std_dev: sqrt(variance),
+            item_counts: elves.iter().map(|elf| elf.items().len()).collect(),
+            sorted_totals,
+        })
+    }
+
+    /// Nearest-rank percentile of the totals, `percent` is clamped to 100
+    pub fn percentile(&self, percent: u8) -> T {
+        let percent = percent.min(100) as usize;
+        let rank = (percent * self.elves).div_ceil(100).max(1);
+        self.sorted_totals[rank - 1]
+    }
+
+    /// Number of snacks of each elf, in inventory order
+    pub fn item_counts(&self) -> &[usize] {
+        &self.item_counts
+    }
+
+    pub fn mean_item_count(&self) -> f64 {
+        self.item_counts.iter().sum::<usize>() as f64 / self.elves as f64
+    }
+
+    /// Number of elves per bucket, the buckets splitting `min..=max` evenly.
+    /// Returns `(bucket start, bucket end, elves)` triples.
+    pub fn buckets(&self, buckets: usize) -> Vec<(T, T, usize)> {
+        let (min, max) = (self.min.as_u128(), self.max.as_u128());
+        let span = (max - min).saturating_add(1);
+        let width = span.div_ceil(buckets.max(1) as u128).max(1);
+
+        let mut counts = Vec::new();
+        let mut start = Some(min);
+        while let Some(first) = start.filter(|&first| first <= max) {
+            let end = first.saturating_add(width - 1).min(max);
+            let count = self.sorted_totals
+                .iter()
+                .filter(|total| (first..=end).contains(&total.as_u128()))
+                .count();
+            counts.push((T::from_u128(first), T::from_u128(end), count));
+            start = first.checked_add(width);
+        }
+        counts
+    }
+
+    /// Text histogram of the totals, the longest bar being `width` characters
+    pub fn histogram(&self, buckets: usize, width: usize) -> String {
+        let buckets = self.buckets(buckets);
+        let most = buckets.iter().map(|&(_, _, count)| count).max().unwrap_or(0).max(1);
+        let digits = decimal_len(self.max.as_u128());
+
+        let mut s = String::new();
+        for (start, end, count) in buckets {
+            let bar = (count * width).div_ceil(most);
+            writeln!(
+                s,
+                "{:>digits$}-{:>digits$} | {:<width$} {}",
+                start,
+                end,
+                "#".repeat(bar),
+                count,
+                digits = digits,
+                width = width
+            ).unwrap();
+        }
+        s
+    }
+}
+
+impl<T: Calories> fmt::Display for CalorieStats<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "elves:   {}", self.elves)?;
+        writeln!(f, "min:     {}", self.min)?;
+        writeln!(f, "max:     {}", self.max)?;
+        writeln!(f, "mean:    {:.1}", self.mean)?;
+        writeln!(f, "median:  {:.1}", self.median)?;
+        writeln!(f, "std dev: {:.1}", self.std_dev)?;
+        writeln!(
+            f,
+            "p25/p75/p90/p99: {}/{}/{}/{}",
+            self.percentile(25),
+            self.percentile(75),
+            self.percentile(90),
+            self.percentile(99)
+        )?;
+        writeln!(
+            f,
+            "snacks per elf: min {}, mean {:.1}, max {}",
+            self.item_counts.iter().min().unwrap(),
+            self.mean_item_count(),
+            self.item_counts.iter().max().unwrap()
+        )
+    }
+}
+
+fn decimal_len(n: u128) -> usize {
+    n.checked_ilog10().unwrap_or(0) as usize + 1
+}
+
+/// Newton's method, `f64::sqrt` is not available without `std`
+fn sqrt(x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    let mut guess = if x < 1.0 { 1.0 } else { x };
+    loop {
+        let next = (guess + x / guess) / 2.0;
+        if next >= guess {
+            return guess;
+        }
+        guess = next;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day1::{parse_calories, parse_calories_as};
+    use crate::day1::stats::{CalorieStats, StatsError};
+
+    #[test]
+    fn test_example_stats() {
+        let example = include_str!("example.txt");
+        let stats = CalorieStats::new(&parse_calories(example).unwrap()).unwrap();
+
+        assert_eq!((stats.elves, stats.min, stats.max), (5, 4000, 24000));
+        assert_eq!(stats.mean, 11000.0);
+        assert_eq!(stats.median, 10000.0);
+        assert!((stats.std_dev - 6985.70).abs() < 0.01);
+        assert_eq!(stats.item_counts(), &[3, 1, 2, 3, 1]);
+        assert_eq!(
+            (stats.percentile(0), stats.percentile(50), stats.percentile(80), stats.percentile(100)),
+            (4000, 10000, 11000, 24000)
+        );
+    }
+
+    #[test]
+    fn test_histogram() {
+        let example = include_str!("example.txt");
+        let stats = CalorieStats::new(&parse_calories(example).unwrap()).unwrap();
+
+        assert_eq!(
+            stats.buckets(2),
+            vec![(4000, 14000, 4), (14001, 24000, 1)]
+        );
+        assert_eq!(
+            stats.histogram(2, 8),
+            " 4000-14000 | ######## 4\n14001-24000 | ##       1\n"
+        );
+        assert_eq!(CalorieStats::<u32>::new(&[]), Err(StatsError::NoElves));
+        assert_eq!(
+            CalorieStats::new(&parse_calories("4000000000\n300000000").unwrap()),
+            Err(StatsError::Overflow)
+        );
+        assert_eq!(
+            CalorieStats::new(&parse_calories_as::<u64>("4000000000\n300000000").unwrap()).unwrap().max,
+            4300000000
+        );
+    }
+}
@@ -0,0 +1,143 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use crate::day2::{GameResult, Rules, Shape};
+
+/// How a guide fares against the opponent moves it was written for. The
+/// opponent column is treated as a distribution over shapes: each shape is
+/// assumed to come up as often as it does in the guide.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Analysis {
+    pub rounds: u32,
+    /// Rounds per opponent shape, indexed by `Shape::index`
+    pub opponent_counts: Vec<u32>,
+    /// Total score of following the guide
+    pub guide_score: u32,
+    /// The single shape scoring the most if played every round
+    pub best_fixed: Shape,
+    pub best_fixed_score: u32,
+    /// The best answer to each opponent shape, indexed by `Shape::index`
+    pub best_responses: Vec<Shape>,
+    /// Total score of always playing the best answer
+    pub optimal_score: u32,
+}
+
+/// The shape scoring the most against `opponent`, the first one on ties
+pub fn best_response(opponent: Shape, rules: &Rules) -> Shape {
+    rules
+        .all_shapes()
+        .rev()
+        .max_by_key(|&own| GameResult(opponent, own).score(rules))
+        .unwrap()
+}
+
+impl Analysis {
+    pub fn new(results: impl Iterator<Item=GameResult>, rules: &Rules) -> Self {
+        let mut opponent_counts = vec![0; rules.shapes()];
+        let mut guide_score = 0;
+        for result in results {
+            opponent_counts[result.0.index()] += 1;
+            guide_score += result.score(rules);
+        }
+
+        let score_against_all = |own: Shape| -> u32 {
+            rules
+                .all_shapes()
+                .map(|opponent| opponent_counts[opponent.index()] * GameResult(opponent, own).score(rules))
+                .sum()
+        };
+        let best_fixed = rules
+            .all_shapes()
+            .rev()
+            .max_by_key(|&own| score_against_all(own))
+            .unwrap();
+
+        let best_responses: Vec<Shape> = rules
+            .all_shapes()
+            .map(|opponent| best_response(opponent, rules))
+            .collect();
+        let optimal_score = rules
+            .all_shapes()
+            .map(|opponent| {
+                let own = best_responses[opponent.index()];
+                opponent_counts[opponent.index()] * GameResult(opponent, own).score(rules)
+            })
+            .sum();
+
+        Self {
+            rounds: opponent_counts.iter().sum(),
+            best_fixed_score: score_against_all(best_fixed),
+            opponent_counts,
+            guide_score,
+            best_fixed,
+            best_responses,
+            optimal_score,
+        }
+    }
+
+    /// Share of rounds for each opponent shape
+    pub fn opponent_distribution(&self) -> Vec<f64> {
+        self.opponent_counts
+            .iter()
+            .map(|&count| count as f64 / self.rounds.max(1) as f64)
+            .collect()
+    }
+
+    /// Average score per round of following the guide
+    pub fn expected_score(&self) -> f64 {
+        self.guide_score as f64 / self.rounds.max(1) as f64
+    }
+
+    /// Average score per round of always playing the best answer
+    pub fn optimal_expected_score(&self) -> f64 {
+        self.optimal_score as f64 / self.rounds.max(1) as f64
+    }
+
+    /// Points the guide leaves on the table compared to the best answers
+    pub fn gap(&self) -> u32 {
+        self.optimal_score - self.guide_score
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day2::{parse_scores, parse_strategies, Rules, Shape, strategy_to_result};
+    use crate::day2::analysis::{Analysis, best_response};
+
+    #[test]
+    fn test_example_analysis() {
+        let example = include_str!("example.txt");
+        let rules = Rules::default();
+        let analysis = Analysis::new(parse_scores(example, &rules).unwrap(), &rules);
+
+        assert_eq!(analysis.opponent_counts, vec![1, 1, 1]);
+        assert_eq!(analysis.best_responses, vec![Shape::PAPER, Shape::SCISSORS, Shape::ROCK]);
+        assert_eq!((analysis.best_fixed, analysis.best_fixed_score), (Shape::SCISSORS, 18));
+        assert_eq!((analysis.guide_score, analysis.optimal_score, analysis.gap()), (15, 24, 9));
+        assert_eq!((analysis.expected_score(), analysis.optimal_expected_score()), (5.0, 8.0));
+    }
+
+    #[test]
+    fn test_input_analysis() {
+        let input = include_str!("input.txt");
+        let rules = Rules::default();
+        let analysis = Analysis::new(
+            parse_strategies(input, &rules).unwrap().map(|strategy| strategy_to_result(strategy, &rules)),
+            &rules
+        );
+
+        assert_eq!(analysis.rounds, 2500);
+        assert_eq!(analysis.guide_score, 15442);
+        assert!(analysis.best_fixed_score <= analysis.optimal_score);
+        assert!(analysis.guide_score <= analysis.optimal_score);
+        assert!((analysis.opponent_distribution().iter().sum::<f64>() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_best_response_with_custom_scores() {
+        let rules = Rules { shape_scores: vec![10, 0, 0], ..Rules::default() };
+
+        // Rock's 10 points beat the 6 for winning with Paper
+        assert_eq!(best_response(Shape::ROCK, &rules), Shape::ROCK);
+        assert_eq!(best_response(Shape::SCISSORS, &rules), Shape::ROCK);
+    }
+}
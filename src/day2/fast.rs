@@ -0,0 +1,193 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use crate::day2::{GameResult, Outcome, Rules, Shape};
+use crate::day2::infer::Reading;
+
+const BOM: &[u8] = "\u{feff}".as_bytes();
+const NOT_A_LETTER: u8 = u8::MAX;
+
+/// Scores guide lines without going through `str` or `FromStr`: the two
+/// letters of a line index straight into a table of round scores. Gives the
+/// same totals as `sum_scores` over `parse_scores` or `parse_strategies`,
+/// skipping the same lines they skip.
+#[derive(Debug, Clone)]
+pub struct ScoreTable {
+    /// Column index of each byte, `NOT_A_LETTER` for bytes that are not one
+    first: [u8; 256],
+    second: [u8; 256],
+    columns: usize,
+    /// Score of a round, indexed by `first * columns + second`
+    scores: Vec<u32>,
+}
+
+impl ScoreTable {
+    /// Letters have to be ASCII and `rules` have to validate
+    pub fn new(rules: &Rules, reading: Reading) -> Result<Self, String> {
+        rules.validate()?;
+        let second_letters: &[char] = match reading {
+            Reading::Shapes => &rules.own_letters,
+            Reading::Outcomes => &rules.outcome_letters,
+        };
+        let index = |letters: &[char]| -> Result<[u8; 256], String> {
+            let mut table = [NOT_A_LETTER; 256];
+            for (idx, &letter) in letters.iter().enumerate() {
+                if !letter.is_ascii() {
+                    return Err("The fast path needs ASCII letters".to_string());
+                }
+                table[letter as usize] = idx as u8;
+            }
+            Ok(table)
+        };
+
+        let columns = second_letters.len();
+        let scores = rules
+            .all_shapes()
+            .flat_map(|opponent| (0..columns).map(move |second| (opponent, second)))
+            .map(|(opponent, second)| match reading {
+                Reading::Shapes => GameResult(opponent, Shape(second)).score(rules),
+                Reading::Outcomes => {
+                    let own = rules.response(opponent, Outcome::ALL[second]);
+                    GameResult(opponent, own).score(rules)
+                }
+            })
+            .collect();
+
+        Ok(Self {
+            first: index(&rules.opponent_letters)?,
+            second: index(second_letters)?,
+            columns,
+            scores,
+        })
+    }
+
+    /// Score of one line without its line ending, `None` if `parse_scores` would
+    /// skip it
+    fn line(&self, line: &[u8]) -> Option<u32> {
+        let [first, b' ', second, rest @ ..] = line else {
+            return None;
+        };
+        let (first, second) = (self.first[*first as usize], self.second[*second as usize]);
+        if first == NOT_A_LETTER || second == NOT_A_LETTER {
+            return None;
+        }
+        // Anything after the letters has to be a new word or trailing
+        // whitespace, which `normalize::lines` trims off
+        let ends = match rest {
+            [] | [b' ', ..] => true,
+            _ => core::str::from_utf8(rest).is_ok_and(|rest| rest.trim_end().is_empty()),
+        };
+        ends.then(|| self.scores[first as usize * self.columns + second as usize])
+    }
+
+    /// Total score of the guide lines in `input`, a whole guide or a chunk of
+    /// one cut at line boundaries
+    pub fn score(&self, input: &[u8]) -> u32 {
+        input
+            .strip_prefix(BOM)
+            .unwrap_or(input)
+            // A `\r\n` leaves an empty line behind, which scores nothing
+            .split(|&byte| byte == b'\n' || byte == b'\r')
+            .filter_map(|line| self.line(line))
+            .sum()
+    }
+
+    /// Like `score`, cutting `input` into `threads` chunks at line boundaries
+    /// and scoring them in parallel
+    #[cfg(feature = "std")]
+    pub fn score_parallel(&self, input: &[u8], threads: usize) -> u32 {
+        let input = input.strip_prefix(BOM).unwrap_or(input);
+        let chunk = input.len() / threads.max(1) + 1;
+
+        let mut chunks = Vec::new();
+        let mut start = 0;
+        while start < input.len() {
+            let end = match input[(start + chunk).min(input.len())..].iter().position(|&byte| byte == b'\n') {
+                Some(newline) => start + chunk + newline + 1,
+                None => input.len(),
+            };
+            chunks.push(&input[start..end]);
+            start = end;
+        }
+
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = chunks
+                .into_iter()
+                .map(|chunk| scope.spawn(move || self.score(chunk)))
+                .collect();
+            handles.into_iter().map(|handle| handle.join().unwrap()).sum()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test::Bencher;
+    use crate::day2::{parse_scores, parse_strategies, Rules, strategy_to_result, sum_scores};
+    use crate::day2::fast::ScoreTable;
+    use crate::day2::infer::Reading;
+
+    fn slow(input: &str, rules: &Rules, reading: Reading) -> u32 {
+        match reading {
+            Reading::Shapes => sum_scores(parse_scores(input, rules).unwrap(), rules),
+            Reading::Outcomes => sum_scores(
+                parse_strategies(input, rules).unwrap().map(|strategy| strategy_to_result(strategy, rules)),
+                rules
+            ),
+        }
+    }
+
+    #[test]
+    fn test_matches_sum_scores() {
+        let rules = Rules::default();
+        for input in [include_str!("example.txt"), include_str!("input.txt")] {
+            for reading in [Reading::Shapes, Reading::Outcomes] {
+                let table = ScoreTable::new(&rules, reading).unwrap();
+                assert_eq!(table.score(input.as_bytes()), slow(input, &rules, reading));
+            }
+        }
+        assert_eq!(
+            ScoreTable::new(&rules, Reading::Outcomes).unwrap().score(include_str!("input.txt").as_bytes()),
+            15442
+        );
+    }
+
+    #[test]
+    fn test_matches_sum_scores_on_messy_lines() {
+        let input = "\u{feff}A Y\r\nB X extra\nC Z\t \n\nA  Y\nAY\n B X\nC Z\rA X\nD X\nA Y\u{a0}\nB Zz\n";
+        for rules in [Rules::default(), Rules::cyclic(5).unwrap()] {
+            for reading in [Reading::Shapes, Reading::Outcomes] {
+                let table = ScoreTable::new(&rules, reading).unwrap();
+                assert_eq!(table.score(input.as_bytes()), slow(input, &rules, reading));
+            }
+        }
+    }
+
+    #[test]
+    fn test_rejects_mismatched_rules() {
+        let rules = Rules { own_letters: vec!['V', 'W', 'X', 'Y', 'Z'], ..Rules::default() };
+
+        assert!(ScoreTable::new(&rules, Reading::Shapes).is_err());
+        assert!(ScoreTable::new(&rules, Reading::Outcomes).is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_parallel_matches_sequential() {
+        let input = include_str!("input.txt").repeat(3);
+        let table = ScoreTable::new(&Rules::default(), Reading::Shapes).unwrap();
+
+        let expected = table.score(input.as_bytes());
+
+        for threads in [0, 1, 2, 7, 64, 100000] {
+            assert_eq!(table.score_parallel(input.as_bytes(), threads), expected);
+        }
+    }
+
+    #[bench]
+    fn bench_input1(b: &mut Bencher) {
+        let input = include_str!("input.txt");
+        let table = ScoreTable::new(&Rules::default(), Reading::Shapes).unwrap();
+
+        b.iter(|| table.score(input.as_bytes()));
+    }
+}
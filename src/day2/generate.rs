@@ -0,0 +1,180 @@
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use crate::day2::{GameResult, Outcome, Rules, Shape};
+
+/// Which of the guides scoring the target `generate` returns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Objective {
+    /// Any of them
+    AnyGuide,
+    /// One winning as few rounds as possible
+    FewestWins,
+}
+
+/// No choice of own shapes scores `target` against the opponent moves.
+/// Any guide scores somewhere between `min` and `max`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Unreachable {
+    pub target: u32,
+    pub min: u32,
+    pub max: u32,
+}
+
+impl fmt::Display for Unreachable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "no guide scores exactly {}, totals range from {} to {}",
+            self.target, self.min, self.max
+        )
+    }
+}
+
+/// Own shapes, one per opponent move, for a guide totalling exactly
+/// `target`. Works through the totals reachable after each round, so takes
+/// O(rounds * target * shapes) time and a byte per round and total, after
+/// rejecting targets outside the range any guide can score.
+pub fn generate(opponent: &[Shape], target: u32, rules: &Rules, objective: Objective) -> Result<Vec<GameResult>, Unreachable> {
+    const UNREACHED: u32 = u32::MAX;
+
+    // For every round the score and whether it is a win for each own shape
+    let rounds: Vec<Vec<(Shape, u32, u32)>> = opponent
+        .iter()
+        .map(|&theirs| {
+            rules
+                .all_shapes()
+                .map(|own| {
+                    let result = GameResult(theirs, own);
+                    (own, result.score(rules), (result.outcome(rules) == Outcome::Win) as u32)
+                })
+                .collect()
+        })
+        .collect();
+    let (mut min, mut max) = (0u32, 0u32);
+    for scores in &rounds {
+        min = min.saturating_add(scores.iter().map(|&(_, score, _)| score).min().unwrap_or(0));
+        max = max.saturating_add(scores.iter().map(|&(_, score, _)| score).max().unwrap_or(0));
+    }
+    if target < min || target > max {
+        return Err(Unreachable { target, min, max });
+    }
+
+    let width = target as usize + 1;
+
+    // Fewest wins needed to reach each total so far, and for every round
+    // and total the shape played to get there
+    let mut wins = vec![UNREACHED; width];
+    wins[0] = 0;
+    let mut choices = vec![0u8; opponent.len() * width];
+
+    for (round, scores) in rounds.iter().enumerate() {
+        let mut next = vec![UNREACHED; width];
+        for (total, &so_far) in wins.iter().enumerate() {
+            if so_far == UNREACHED {
+                continue;
+            }
+            for &(own, score, won) in scores {
+                let reached = total + score as usize;
+                let cost = match objective {
+                    Objective::AnyGuide => 0,
+                    Objective::FewestWins => so_far + won,
+                };
+                if reached < width && cost < next[reached] {
+                    next[reached] = cost;
+                    choices[round * width + reached] = own.index() as u8;
+                }
+            }
+        }
+        wins = next;
+    }
+
+    if wins[target as usize] == UNREACHED {
+        return Err(Unreachable { target, min, max });
+    }
+
+    let mut results = Vec::with_capacity(opponent.len());
+    let mut total = target as usize;
+    for (round, &theirs) in opponent.iter().enumerate().rev() {
+        let own = Shape(choices[round * width + total] as usize);
+        let result = GameResult(theirs, own);
+        total -= result.score(rules) as usize;
+        results.push(result);
+    }
+    results.reverse();
+    Ok(results)
+}
+
+/// A guide listing own shapes, readable by `parse_scores` with the same rules
+pub fn to_guide(results: &[GameResult], rules: &Rules) -> String {
+    let mut guide = String::with_capacity(results.len() * 4);
+    for result in results {
+        guide.push(rules.opponent_letters[result.0.index()]);
+        guide.push(' ');
+        guide.push(rules.own_letters[result.1.index()]);
+        guide.push('\n');
+    }
+    guide
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day2::{GameResult, Outcome, parse_scores, Rules, Shape, sum_scores};
+    use crate::day2::generate::{generate, Objective, to_guide, Unreachable};
+
+    #[test]
+    fn test_generate_example_total() {
+        let rules = Rules::default();
+        let opponent = [Shape::ROCK, Shape::PAPER, Shape::SCISSORS];
+        let results = generate(&opponent, 15, &rules, Objective::AnyGuide).unwrap();
+        let guide = to_guide(&results, &rules);
+
+        assert_eq!(
+            sum_scores(parse_scores(&guide, &rules).unwrap(), &rules),
+            15
+        );
+        assert_eq!(
+            parse_scores(&guide, &rules).unwrap().map(|result| result.0).collect::<Vec<Shape>>(),
+            opponent.to_vec()
+        );
+    }
+
+    #[test]
+    fn test_generate_fewest_wins() {
+        let rules = Rules::default();
+        let opponent = [Shape::ROCK; 4];
+        // Rounds against rock score 3, 4 or 8, so 20 needs a win: one win
+        // and three draws will do
+        let results = generate(&opponent, 20, &rules, Objective::FewestWins).unwrap();
+        let wins = results.iter().filter(|result| result.outcome(&rules) == Outcome::Win).count();
+
+        assert_eq!(sum_scores(results.iter().copied(), &rules), 20);
+        assert_eq!(wins, 1);
+        assert_eq!(
+            generate(&opponent, 32, &rules, Objective::FewestWins).unwrap(),
+            vec![GameResult(Shape::ROCK, Shape::PAPER); 4]
+        );
+    }
+
+    #[test]
+    fn test_generate_impossible() {
+        let rules = Rules::default();
+        let opponent = [Shape::ROCK, Shape::ROCK];
+
+        // Against rock a round scores 3, 4 or 8
+        assert_eq!(
+            generate(&opponent, 9, &rules, Objective::AnyGuide),
+            Err(Unreachable { target: 9, min: 6, max: 16 })
+        );
+        assert!(generate(&opponent, 11, &rules, Objective::AnyGuide).is_ok());
+        assert_eq!(
+            generate(&opponent, 17, &rules, Objective::AnyGuide).unwrap_err().to_string(),
+            "no guide scores exactly 17, totals range from 6 to 16"
+        );
+        assert_eq!(
+            generate(&[Shape::ROCK], u32::MAX, &rules, Objective::AnyGuide),
+            Err(Unreachable { target: u32::MAX, min: 3, max: 8 })
+        );
+    }
+}
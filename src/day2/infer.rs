@@ -0,0 +1,170 @@
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use crate::day2::{GameResult, Outcome, Rules, Shape};
+use crate::normalize;
+
+/// How the second column of a guide is read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reading {
+    /// The letters are own shapes, as in `parse_scores`
+    Shapes,
+    /// The letters are outcomes, as in `parse_strategies`
+    Outcomes,
+}
+
+/// One way to read a guide. `rules` has the second column letters permuted to
+/// match, so it can be passed on to `parse_scores` or `parse_strategies`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Interpretation {
+    pub reading: Reading,
+    pub rules: Rules,
+    pub score: u32,
+}
+
+/// Every ordering of `items`
+fn permutations<T: Clone>(items: &[T]) -> Vec<Vec<T>> {
+    if items.len() <= 1 {
+        return vec![items.to_vec()];
+    }
+
+    let mut all = vec![];
+    for (i, first) in items.iter().enumerate() {
+        let mut rest = items.to_vec();
+        rest.remove(i);
+        for mut permutation in permutations(&rest) {
+            permutation.insert(0, first.clone());
+            all.push(permutation);
+        }
+    }
+    all
+}
+
+/// The letter of a single-character column
+fn single_letter(column: &str) -> Option<char> {
+    let mut chars = column.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c),
+        _ => None,
+    }
+}
+
+/// How many times each (opponent shape, second column letter) pair occurs.
+/// Lines with an unknown opponent letter or a second column that is not a
+/// single letter are skipped like the parsers do.
+fn count_rounds(guide: &str, rules: &Rules) -> BTreeMap<(Shape, char), u32> {
+    let mut counts = BTreeMap::new();
+    for line in normalize::lines(guide) {
+        let mut columns = line.split(" ");
+        if let (Some(opponent), Some(own)) = (columns.next(), columns.next()) {
+            if let (Ok(opponent), Some(own)) = (rules.opponent_shape(opponent), single_letter(own)) {
+                *counts.entry((opponent, own)).or_insert(0) += 1;
+            }
+        }
+    }
+    counts
+}
+
+/// Every reading of the guide's second column: each assignment of the
+/// letters of `rules.own_letters` to shapes and each assignment of the
+/// letters of `rules.outcome_letters` to outcomes, with the total score of
+/// the guide under it. Fails if `rules` do not validate.
+pub fn interpretations(guide: &str, rules: &Rules) -> Result<Vec<Interpretation>, String> {
+    rules.validate()?;
+    let counts = count_rounds(guide, rules);
+    let mut all = vec![];
+
+    for own_letters in permutations(&rules.own_letters) {
+        let rules = Rules { own_letters, ..rules.clone() };
+        let score = counts
+            .iter()
+            .filter_map(|(&(opponent, letter), &count)| {
+                let own = rules.own_letters.iter().position(|&l| l == letter)?;
+                Some(count * GameResult(opponent, Shape(own)).score(&rules))
+            })
+            .sum();
+        all.push(Interpretation { reading: Reading::Shapes, rules, score });
+    }
+
+    for outcome_letters in permutations(&rules.outcome_letters) {
+        let rules = Rules { outcome_letters: outcome_letters.try_into().unwrap(), ..rules.clone() };
+        let score = counts
+            .iter()
+            .filter_map(|(&(opponent, letter), &count)| {
+                let outcome = Outcome::ALL[rules.outcome_letters.iter().position(|&l| l == letter)?];
+                Some(count * GameResult(opponent, rules.response(opponent, outcome)).score(&rules))
+            })
+            .sum();
+        all.push(Interpretation { reading: Reading::Outcomes, rules, score });
+    }
+
+    Ok(all)
+}
+
+/// The readings of the guide that give exactly `total`
+pub fn consistent_interpretations(guide: &str, rules: &Rules, total: u32) -> Result<Vec<Interpretation>, String> {
+    Ok(interpretations(guide, rules)?
+        .into_iter()
+        .filter(|interpretation| interpretation.score == total)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day2::{parse_scores, parse_strategies, Rules, strategy_to_result, sum_scores};
+    use crate::day2::infer::{consistent_interpretations, interpretations, Reading};
+
+    #[test]
+    fn test_example_interpretations() {
+        let example = include_str!("example.txt");
+        let all = interpretations(example, &Rules::default()).unwrap();
+
+        assert_eq!(all.len(), 12);
+        assert_eq!(
+            all.iter().map(|i| i.score).collect::<Vec<u32>>(),
+            vec![15, 6, 15, 15, 15, 24, 12, 15, 15, 18, 15, 15]
+        );
+        assert_eq!(all[0].rules, Rules::default());
+        assert_eq!(all[6].rules, Rules::default());
+    }
+
+    #[test]
+    fn test_scores_match_parsers() {
+        let messy = "\u{feff}A Y\r\nB X extra\nC Z\t \n\nA  Y\nAY\n B X\nC Z\rA X\nD X\nA Y\u{a0}\nB Zz\nD \r\rD ZBC\n";
+        let cases = [
+            (include_str!("input.txt"), Rules::default()),
+            (messy, Rules::default()),
+            (messy, Rules::cyclic(5).unwrap()),
+            ("D \r\rD ZBC", Rules::cyclic(5).unwrap()),
+        ];
+
+        for (input, rules) in cases {
+            for interpretation in interpretations(input, &rules).unwrap() {
+                let rules = &interpretation.rules;
+                let score = match interpretation.reading {
+                    Reading::Shapes => sum_scores(parse_scores(input, rules).unwrap(), rules),
+                    Reading::Outcomes => sum_scores(
+                        parse_strategies(input, rules).unwrap().map(|strategy| strategy_to_result(strategy, rules)),
+                        rules
+                    ),
+                };
+                assert_eq!(interpretation.score, score);
+            }
+        }
+    }
+
+    #[test]
+    fn test_input_consistent_interpretations() {
+        let input = include_str!("input.txt");
+        let found = consistent_interpretations(input, &Rules::default(), 15442).unwrap();
+
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].reading, Reading::Outcomes);
+        assert_eq!(found[0].rules, Rules::default());
+        assert!(consistent_interpretations(input, &Rules::default(), 1).unwrap().is_empty());
+
+        let mismatched = Rules { shape_scores: vec![1, 2, 3, 4, 5], ..Rules::default() };
+        assert!(interpretations(input, &mismatched).is_err());
+    }
+}
@@ -1,56 +1,64 @@
-use std::str::FromStr;
+pub mod analysis;
+pub mod fast;
+pub mod generate;
+pub mod infer;
+pub mod player;
+pub mod predict;
+pub mod stats;
+pub mod tournament;
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::str::FromStr;
+use crate::normalize;
+
+/// A shape by its position in the game's cycle. In a game of `n` shapes each
+/// shape beats the shapes an odd number of steps behind it and loses to the
+/// ones an even number of steps behind, so with Rock, Paper and Scissors at
+/// 0, 1 and 2 Paper beats Rock, Scissors beats Paper and Rock beats Scissors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Shape(pub usize);
 
-#[derive(Clone, Copy)]
-enum Shape {
-    Rock,
-    Paper,
-    Scissors
+impl Shape {
+    pub const ROCK: Shape = Shape(0);
+    pub const PAPER: Shape = Shape(1);
+    pub const SCISSORS: Shape = Shape(2);
+    /// Rock-Paper-Scissors-Spock-Lizard in cycle order
+    pub const SPOCK: Shape = Shape(3);
+    pub const LIZARD: Shape = Shape(4);
+
+    pub fn index(&self) -> usize {
+        self.0
+    }
+
+    pub fn score(&self, rules: &Rules) -> u32 {
+        rules.shape_scores[self.index()]
+    }
+
+    /// Steps from `other` forward to `self` in a game of `shapes` shapes
+    fn distance_from(&self, other: Shape, shapes: usize) -> usize {
+        (self.0 + shapes - other.0) % shapes
+    }
 }
 
 impl FromStr for Shape {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        use Shape::*;
-        match s {
-            "A" => Ok(Rock),
-            "B" => Ok(Paper),
-            "C" => Ok(Scissors),
-            "X" => Ok(Rock),
-            "Y" => Ok(Paper),
-            "Z" => Ok(Scissors),
-            _ => Err("Oh no".to_string())
-        }
+        let rules = Rules::default();
+        rules.opponent_shape(s).or_else(|_| rules.own_shape(s))
     }
 }
 
-impl Shape {
-    fn score(&self) -> u32 {
-        match self {
-            Shape::Rock => 1,
-            Shape::Paper => 2,
-            Shape::Scissors => 3,
-        }
-    }
-}
-
-struct GameResult(Shape, Shape);
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GameResult(pub Shape, pub Shape);
 
 impl FromStr for GameResult {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut shapes = s
-            .split(" ")
-            .map(Shape::from_str)
-            .take(2);
-
-        Ok(
-            Self(
-                shapes.next().ok_or_else(no_next)??,
-                shapes.next().ok_or_else(no_next)??
-            )
-        )
+        Rules::default().parse_result(s)
     }
 }
 
@@ -58,56 +66,214 @@ fn no_next() -> String {
     "No next value".to_string()
 }
 
-enum Outcome {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
     Win,
     Loss,
     Draw
 }
 
 impl Outcome {
-    fn score(&self) -> u32 {
+    /// From worst to best
+    pub const ALL: [Outcome; 3] = [Outcome::Loss, Outcome::Draw, Outcome::Win];
+
+    pub fn index(&self) -> usize {
         match self {
-            Outcome::Win => 6,
             Outcome::Loss => 0,
-            Outcome::Draw => 3
+            Outcome::Draw => 1,
+            Outcome::Win => 2,
         }
     }
+
+    pub fn score(&self, rules: &Rules) -> u32 {
+        rules.outcome_scores[self.index()]
+    }
 }
 
 impl GameResult {
-    fn outcome(&self) -> Outcome {
-        use Shape::*;
-        use Outcome::*;
-
-        match (&self.0, &self.1) {
-            (Rock, Paper) => Win,
-            (Paper, Rock) => Loss,
-            (Paper, Scissors) => Win,
-            (Scissors, Paper) => Loss,
-            (Scissors, Rock) => Win,
-            (Rock, Scissors) => Loss,
-            _ => Draw
+    pub fn outcome(&self, rules: &Rules) -> Outcome {
+        match self.1.distance_from(self.0, rules.shapes()) {
+            0 => Outcome::Draw,
+            d if d % 2 == 1 => Outcome::Win,
+            _ => Outcome::Loss,
         }
     }
 
-    fn own_shape(&self) -> &Shape {
+    pub fn own_shape(&self) -> &Shape {
         &self.1
     }
 
-    fn score(&self) -> u32 {
-        self.own_shape().score() + self.outcome().score()
+    pub fn score(&self, rules: &Rules) -> u32 {
+        self.own_shape().score(rules) + self.outcome(rules).score(rules)
+    }
+}
+
+/// Scoring and the meaning of the letters in a strategy guide. The default is
+/// the puzzle's: 1, 2 and 3 points for Rock, Paper and Scissors, 0, 3 and 6
+/// for a loss, draw and win, the opponent playing A, B and C and the second
+/// column reading X, Y and Z either as shapes or as loss, draw and win.
+///
+/// The number of shapes in the game is the length of `shape_scores`, which
+/// has to match `opponent_letters` and `own_letters` and be odd, which
+/// `Rules::new` and `validate` check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rules {
+    /// Indexed by `Shape::index`
+    pub shape_scores: Vec<u32>,
+    /// Indexed by `Outcome::index`
+    pub outcome_scores: [u32; 3],
+    /// The opponent's letter for each shape, indexed by `Shape::index`
+    pub opponent_letters: Vec<char>,
+    /// Own letter for each shape when the guide lists shapes
+    pub own_letters: Vec<char>,
+    /// Own letter for each outcome when the guide lists outcomes, indexed by
+    /// `Outcome::index`
+    pub outcome_letters: [char; 3],
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self::cyclic(3).unwrap()
+    }
+}
+
+impl Rules {
+    pub fn new(
+        shape_scores: Vec<u32>,
+        outcome_scores: [u32; 3],
+        opponent_letters: Vec<char>,
+        own_letters: Vec<char>,
+        outcome_letters: [char; 3],
+    ) -> Result<Self, String> {
+        let rules = Self { shape_scores, outcome_scores, opponent_letters, own_letters, outcome_letters };
+        rules.validate()?;
+        Ok(rules)
+    }
+
+    /// Checks that there is an odd number of shapes, at least three so that
+    /// every shape can win and lose, and a letter for each of them in both
+    /// columns. The fields are public, so rules built or changed
+    /// by hand are checked again wherever they are used to parse.
+    pub fn validate(&self) -> Result<(), String> {
+        let shapes = self.shapes();
+        if shapes < 3 || shapes.is_multiple_of(2) {
+            return Err(format!("A game needs an odd number of shapes, at least 3, not {}", shapes));
+        }
+        if self.opponent_letters.len() != shapes || self.own_letters.len() != shapes {
+            return Err(format!(
+                "{} shapes need as many letters, got {} for the opponent and {} for own shapes",
+                shapes,
+                self.opponent_letters.len(),
+                self.own_letters.len()
+            ));
+        }
+        Ok(())
+    }
+
+    /// Puzzle-like rules for a game of `shapes` shapes: shape scores from 1
+    /// up, the opponent's letters from A up and own letters ending at Z, so
+    /// that three shapes give the puzzle's rules and five give
+    /// Rock-Paper-Scissors-Spock-Lizard played with A-E and V-Z.
+    pub fn cyclic(shapes: usize) -> Result<Self, String> {
+        if shapes < 3 || shapes.is_multiple_of(2) || shapes > 25 {
+            return Err("A cyclic game needs an odd number of shapes, from 3 to 25".to_string());
+        }
+
+        let letter = |offset: usize| (b'A' + offset as u8) as char;
+        Ok(Self {
+            shape_scores: (1..=shapes as u32).collect(),
+            outcome_scores: [0, 3, 6],
+            opponent_letters: (0..shapes).map(letter).collect(),
+            own_letters: (26 - shapes..26).map(letter).collect(),
+            outcome_letters: ['X', 'Y', 'Z'],
+        })
+    }
+
+    pub fn shapes(&self) -> usize {
+        self.shape_scores.len()
+    }
+
+    /// Every shape of the game in cycle order
+    pub fn all_shapes(&self) -> impl DoubleEndedIterator<Item=Shape> {
+        (0..self.shapes()).map(Shape)
+    }
+
+    /// The shape giving `outcome` against `opponent`. Of several winning or
+    /// losing shapes the one next to `opponent` in the cycle is picked.
+    pub fn response(&self, opponent: Shape, outcome: Outcome) -> Shape {
+        let shapes = self.shapes();
+        match outcome {
+            Outcome::Win => Shape((opponent.0 + 1) % shapes),
+            Outcome::Loss => Shape((opponent.0 + shapes - 1) % shapes),
+            Outcome::Draw => opponent,
+        }
+    }
+
+    fn letter_index(letters: &[char], s: &str) -> Result<usize, String> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => letters
+                .iter()
+                .position(|&letter| letter == c)
+                .ok_or_else(|| "Oh no".to_string()),
+            _ => Err("Oh no".to_string()),
+        }
+    }
+
+    pub fn opponent_shape(&self, s: &str) -> Result<Shape, String> {
+        Ok(Shape(Self::letter_index(&self.opponent_letters, s)?))
+    }
+
+    pub fn own_shape(&self, s: &str) -> Result<Shape, String> {
+        Ok(Shape(Self::letter_index(&self.own_letters, s)?))
+    }
+
+    pub fn outcome(&self, s: &str) -> Result<Outcome, String> {
+        Ok(Outcome::ALL[Self::letter_index(&self.outcome_letters, s)?])
+    }
+
+    /// A guide line read as the opponent's shape and own shape
+    pub fn parse_result(&self, s: &str) -> Result<GameResult, String> {
+        self.validate()?;
+        let mut line = s
+            .split(" ")
+            .take(2);
+
+        Ok(
+            GameResult(
+                self.opponent_shape(line.next().ok_or_else(no_next)?)?,
+                self.own_shape(line.next().ok_or_else(no_next)?)?
+            )
+        )
+    }
+
+    /// A guide line read as the opponent's shape and the desired outcome
+    pub fn parse_strategy(&self, s: &str) -> Result<Strategy, String> {
+        self.validate()?;
+        let mut line = s
+            .split(" ")
+            .take(2);
+
+        Ok(
+            Strategy(
+                self.opponent_shape(line.next().ok_or_else(no_next)?)?,
+                self.outcome(line.next().ok_or_else(no_next)?)?
+            )
+        )
     }
 }
 
-fn parse_scores(file: &str) -> impl Iterator<Item=GameResult>  + '_{
-    file
-        .lines()
-        .filter_map(|line| line.parse().ok())
+/// Guide lines read as shapes, skipping lines that do not parse. Fails if
+/// `rules` do not validate.
+pub fn parse_scores<'a>(file: &'a str, rules: &'a Rules) -> Result<impl Iterator<Item=GameResult> + 'a, String> {
+    rules.validate()?;
+    Ok(normalize::lines(file)
+        .filter_map(|line| rules.parse_result(line).ok()))
 }
 
-fn sum_scores(results: impl Iterator<Item=GameResult>) -> u32 {
+pub fn sum_scores(results: impl Iterator<Item=GameResult>, rules: &Rules) -> u32 {
     results
-        .map(|result| result.score())
+        .map(|result| result.score(rules))
         .sum()
 }
 
@@ -117,73 +283,47 @@ impl FromStr for Outcome {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        use Outcome::*;
-        match s {
-            "X" => Ok(Loss),
-            "Y" => Ok(Draw),
-            "Z" => Ok(Win),
-            _ => Err("Oh no".to_string())
-        }
+        Rules::default().outcome(s)
     }
 }
 
 
-struct Strategy(Shape, Outcome);
+pub struct Strategy(pub Shape, pub Outcome);
 
 impl FromStr for Strategy {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut line = s
-            .split(" ")
-            .take(2);
-
-        Ok(
-            Self(
-                line.next().ok_or_else(no_next)?.parse()?,
-                line.next().ok_or_else(no_next)?.parse()?
-            )
-        )
+        Rules::default().parse_strategy(s)
     }
 
 }
 
-fn strategy_to_result(Strategy(shape, outcome): Strategy) -> GameResult {
-    use Shape::*;
-    use Outcome::*;
-    match outcome {
-        Win => GameResult(shape, match shape {
-            Rock => Paper,
-            Paper => Scissors,
-            Scissors => Rock,
-        }),
-        Loss => GameResult(shape, match shape {
-            Rock => Scissors,
-            Paper => Rock,
-            Scissors => Paper,
-        }),
-        Draw => GameResult(shape, shape)
-    }
+pub fn strategy_to_result(Strategy(shape, outcome): Strategy, rules: &Rules) -> GameResult {
+    GameResult(shape, rules.response(shape, outcome))
 }
 
-fn parse_strategies(file: &str) -> impl Iterator<Item=Strategy>  + '_{
-    file
-        .lines()
-        .filter_map(|line| line.parse().ok())
+/// Guide lines read as outcomes, skipping lines that do not parse. Fails if
+/// `rules` do not validate.
+pub fn parse_strategies<'a>(file: &'a str, rules: &'a Rules) -> Result<impl Iterator<Item=Strategy> + 'a, String> {
+    rules.validate()?;
+    Ok(normalize::lines(file)
+        .filter_map(|line| rules.parse_strategy(line).ok()))
 }
 
 
 #[cfg(test)]
 mod tests {
-    use crate::day2::{parse_scores, parse_strategies, strategy_to_result, sum_scores};
+    use crate::day2::{GameResult, Outcome, parse_scores, parse_strategies, Rules, Shape, strategy_to_result, sum_scores};
 
     #[test]
     fn test_example() {
         let example = include_str!("example.txt");
-        let scores = parse_scores(example);
+        let rules = Rules::default();
+        let scores = parse_scores(example, &rules).unwrap();
 
         assert_eq!(
-            sum_scores(scores),
+            sum_scores(scores, &rules),
             15
         );
     }
@@ -191,10 +331,11 @@ mod tests {
     #[test]
     fn test_input() {
         let input = include_str!("input.txt");
-        let scores = parse_scores(input);
+        let rules = Rules::default();
+        let scores = parse_scores(input, &rules).unwrap();
 
         assert_eq!(
-            sum_scores(scores),
+            sum_scores(scores, &rules),
             15422
         );
 
@@ -203,9 +344,10 @@ mod tests {
     #[test]
     fn test_example2() {
         let example = include_str!("example.txt");
-        let sum_of_scores: u32 = parse_strategies(example)
-            .map(strategy_to_result)
-            .map(|result| result.score())
+        let rules = Rules::default();
+        let sum_of_scores: u32 = parse_strategies(example, &rules).unwrap()
+            .map(|strategy| strategy_to_result(strategy, &rules))
+            .map(|result| result.score(&rules))
             .sum();
 
         assert_eq!(
@@ -217,9 +359,10 @@ mod tests {
     #[test]
     fn test_input2() {
         let example = include_str!("input.txt");
-        let sum_of_scores: u32 = parse_strategies(example)
-            .map(strategy_to_result)
-            .map(|result| result.score())
+        let rules = Rules::default();
+        let sum_of_scores: u32 = parse_strategies(example, &rules).unwrap()
+            .map(|strategy| strategy_to_result(strategy, &rules))
+            .map(|result| result.score(&rules))
             .sum();
 
         assert_eq!(
@@ -228,4 +371,123 @@ mod tests {
         );
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn test_custom_rules() {
+        let example = include_str!("example.txt");
+        let rules = Rules {
+            shape_scores: vec![10, 20, 30],
+            outcome_scores: [1, 2, 3],
+            own_letters: vec!['Z', 'Y', 'X'],
+            ..Rules::default()
+        };
+
+        // Y is Paper against Rock, X Scissors against Paper and Z Rock against Scissors
+        assert_eq!(
+            sum_scores(parse_scores(example, &rules).unwrap(), &rules),
+            (20 + 3) + (30 + 3) + (10 + 3)
+        );
+    }
+
+    #[test]
+    fn test_custom_letters() {
+        let rules = Rules {
+            opponent_letters: vec!['R', 'P', 'S'],
+            own_letters: vec!['r', 'p', 's'],
+            outcome_letters: ['l', 'd', 'w'],
+            ..Rules::default()
+        };
+
+        assert_eq!(
+            sum_scores(parse_scores("R p\nP r\nS s", &rules).unwrap(), &rules),
+            15
+        );
+        assert_eq!(
+            parse_strategies("R d\nP l\nS w", &rules).unwrap()
+                .map(|strategy| strategy_to_result(strategy, &rules))
+                .map(|result| result.score(&rules))
+                .sum::<u32>(),
+            12
+        );
+        assert_eq!(parse_scores("A X", &rules).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_rejects_mismatched_rules() {
+        let five_own_letters = Rules { own_letters: vec!['V', 'W', 'X', 'Y', 'Z'], ..Rules::default() };
+        let even = Rules { shape_scores: vec![1, 2], ..Rules::default() };
+        let single = Rules { shape_scores: vec![1], opponent_letters: vec!['A'], own_letters: vec!['X'], ..Rules::default() };
+
+        assert!(parse_scores("A Z", &five_own_letters).is_err());
+        assert!(parse_strategies("A Z", &five_own_letters).is_err());
+        assert!(five_own_letters.parse_result("A V").is_err());
+        assert_eq!(
+            even.validate(),
+            Err("A game needs an odd number of shapes, at least 3, not 2".to_string())
+        );
+        assert_eq!(
+            single.validate(),
+            Err("A game needs an odd number of shapes, at least 3, not 1".to_string())
+        );
+        assert!(parse_scores("A X", &single).is_err());
+        assert!(Rules::new(vec![1], [0, 3, 6], vec!['A'], vec!['X'], ['X', 'Y', 'Z']).is_err());
+        assert!(Rules::cyclic(1).is_err());
+        assert!(Rules::cyclic(3).is_ok());
+        assert!(Rules::new(vec![1, 2, 3], [0, 3, 6], vec!['A', 'B', 'C'], vec!['X', 'Y'], ['X', 'Y', 'Z']).is_err());
+        assert_eq!(
+            Rules::new(vec![1, 2, 3], [0, 3, 6], vec!['A', 'B', 'C'], vec!['X', 'Y', 'Z'], ['X', 'Y', 'Z']),
+            Ok(Rules::default())
+        );
+    }
+
+    #[test]
+    fn test_default_rules_are_three_shape_cyclic() {
+        let rules = Rules::default();
+
+        assert_eq!(rules.shapes(), 3);
+        assert_eq!(GameResult(Shape::ROCK, Shape::PAPER).outcome(&rules), Outcome::Win);
+        assert_eq!(GameResult(Shape::ROCK, Shape::SCISSORS).outcome(&rules), Outcome::Loss);
+        assert_eq!(GameResult(Shape::SCISSORS, Shape::ROCK).outcome(&rules), Outcome::Win);
+        assert_eq!(rules.response(Shape::SCISSORS, Outcome::Win), Shape::ROCK);
+        assert_eq!(rules.response(Shape::ROCK, Outcome::Loss), Shape::SCISSORS);
+        assert!(Rules::cyclic(4).is_err());
+    }
+
+    #[test]
+    fn test_rock_paper_scissors_spock_lizard() {
+        let rules = Rules::cyclic(5).unwrap();
+        let beats = |a, b| GameResult(b, a).outcome(&rules) == Outcome::Win;
+
+        assert!(beats(Shape::ROCK, Shape::LIZARD));
+        assert!(beats(Shape::ROCK, Shape::SCISSORS));
+        assert!(beats(Shape::PAPER, Shape::SPOCK));
+        assert!(beats(Shape::SCISSORS, Shape::LIZARD));
+        assert!(beats(Shape::SPOCK, Shape::ROCK));
+        assert!(beats(Shape::LIZARD, Shape::PAPER));
+        assert!(!beats(Shape::LIZARD, Shape::ROCK));
+
+        // Lizard beats Spock, Spock beats Rock and Rock draws Rock
+        assert_eq!(
+            sum_scores(parse_scores("D Z\nA Y\nA V", &rules).unwrap(), &rules),
+            11 + 10 + 4
+        );
+    }
+
+    #[test]
+    fn test_every_shape_beats_half_of_the_others() {
+        for shapes in [3, 5, 7, 9] {
+            let rules = Rules::cyclic(shapes).unwrap();
+            for own in rules.all_shapes() {
+                let wins = rules
+                    .all_shapes()
+                    .filter(|&opponent| GameResult(opponent, own).outcome(&rules) == Outcome::Win)
+                    .count();
+                assert_eq!(wins, (shapes - 1) / 2);
+
+                for outcome in Outcome::ALL {
+                    let response = rules.response(own, outcome);
+                    assert_eq!(GameResult(own, response).outcome(&rules), outcome);
+                }
+            }
+        }
+    }
+}
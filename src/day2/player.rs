@@ -0,0 +1,183 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use crate::day2::{parse_scores, parse_strategies, Rules, Shape};
+use crate::day2::analysis::best_response;
+use crate::rng::Rng;
+
+/// A rock-paper-scissors player taking part in matches. A player sees the
+/// opponent's shape after each round and starts every match afresh.
+pub trait Player {
+    fn name(&self) -> String;
+
+    /// Own shape for `round`, counting from zero within the match
+    fn choose(&mut self, round: usize, rules: &Rules) -> Shape;
+
+    /// What the opponent played in the round just finished
+    fn observe(&mut self, _opponent: Shape) {}
+
+    /// Forget everything learned in the previous match
+    fn reset(&mut self) {}
+}
+
+/// Plays the own shapes of a guide in order, starting over when it runs out.
+#[derive(Debug, Clone)]
+pub struct GuidePlayer {
+    name: String,
+    moves: Vec<Shape>,
+}
+
+impl GuidePlayer {
+    /// The second column read as shapes, as in `parse_scores`
+    pub fn literal(guide: &str, rules: &Rules) -> Result<Self, String> {
+        Ok(Self {
+            name: "literal guide".to_string(),
+            moves: parse_scores(guide, rules)?.map(|result| result.1).collect(),
+        })
+    }
+
+    /// The shapes that give the outcomes of the second column against the
+    /// first, as in `parse_strategies`
+    pub fn outcome_driven(guide: &str, rules: &Rules) -> Result<Self, String> {
+        Ok(Self {
+            name: "outcome guide".to_string(),
+            moves: parse_strategies(guide, rules)?
+                .map(|strategy| rules.response(strategy.0, strategy.1))
+                .collect(),
+        })
+    }
+}
+
+impl Player for GuidePlayer {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn choose(&mut self, round: usize, _rules: &Rules) -> Shape {
+        if self.moves.is_empty() {
+            Shape::ROCK
+        } else {
+            self.moves[round % self.moves.len()]
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AlwaysRock;
+
+impl Player for AlwaysRock {
+    fn name(&self) -> String {
+        "always rock".to_string()
+    }
+
+    fn choose(&mut self, _round: usize, _rules: &Rules) -> Shape {
+        Shape::ROCK
+    }
+}
+
+/// Picks uniformly at random, the same sequence in every match for a seed.
+#[derive(Debug, Clone)]
+pub struct RandomPlayer {
+    seed: u64,
+    rng: Rng,
+}
+
+impl RandomPlayer {
+    pub fn new(seed: u64) -> Self {
+        Self { seed, rng: Rng::new(seed) }
+    }
+}
+
+impl Player for RandomPlayer {
+    fn name(&self) -> String {
+        format!("random ({})", self.seed)
+    }
+
+    fn choose(&mut self, _round: usize, rules: &Rules) -> Shape {
+        Shape(self.rng.below(rules.shapes() as u64) as usize)
+    }
+
+    fn reset(&mut self) {
+        self.rng = Rng::new(self.seed);
+    }
+}
+
+/// Counters the shape the opponent has played most so far.
+#[derive(Debug, Clone, Default)]
+pub struct FrequencyPlayer {
+    counts: Vec<u32>,
+}
+
+impl FrequencyPlayer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Player for FrequencyPlayer {
+    fn name(&self) -> String {
+        "frequency counter".to_string()
+    }
+
+    fn choose(&mut self, _round: usize, rules: &Rules) -> Shape {
+        let most_played = (0..self.counts.len())
+            .rev()
+            .max_by_key(|&shape| self.counts[shape])
+            .map(Shape)
+            .unwrap_or(Shape::ROCK);
+        best_response(most_played, rules)
+    }
+
+    fn observe(&mut self, opponent: Shape) {
+        if self.counts.len() <= opponent.index() {
+            self.counts.resize(opponent.index() + 1, 0);
+        }
+        self.counts[opponent.index()] += 1;
+    }
+
+    fn reset(&mut self) {
+        self.counts = vec![];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day2::{Rules, Shape};
+    use crate::day2::player::{FrequencyPlayer, GuidePlayer, Player, RandomPlayer};
+
+    #[test]
+    fn test_guide_players() {
+        let example = include_str!("example.txt");
+        let rules = Rules::default();
+        let mut literal = GuidePlayer::literal(example, &rules).unwrap();
+        let mut outcome = GuidePlayer::outcome_driven(example, &rules).unwrap();
+
+        assert_eq!(
+            (0..4).map(|round| literal.choose(round, &rules)).collect::<Vec<Shape>>(),
+            vec![Shape::PAPER, Shape::ROCK, Shape::SCISSORS, Shape::PAPER]
+        );
+        assert_eq!(
+            (0..3).map(|round| outcome.choose(round, &rules)).collect::<Vec<Shape>>(),
+            vec![Shape::ROCK, Shape::ROCK, Shape::ROCK]
+        );
+    }
+
+    #[test]
+    fn test_adaptive_players() {
+        let rules = Rules::default();
+        let mut frequency = FrequencyPlayer::new();
+        frequency.observe(Shape::SCISSORS);
+        frequency.observe(Shape::SCISSORS);
+        frequency.observe(Shape::PAPER);
+
+        assert_eq!(frequency.choose(3, &rules), Shape::ROCK);
+        frequency.reset();
+        assert_eq!(frequency.choose(0, &rules), Shape::PAPER);
+
+        let mut random = RandomPlayer::new(7);
+        let first: Vec<Shape> = (0..10).map(|round| random.choose(round, &rules)).collect();
+        random.reset();
+        assert_eq!((0..10).map(|round| random.choose(round, &rules)).collect::<Vec<Shape>>(), first);
+    }
+}
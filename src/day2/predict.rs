@@ -0,0 +1,170 @@
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use crate::day2::{GameResult, Rules, Shape};
+use crate::day2::analysis::best_response;
+use crate::day2::player::Player;
+
+/// Predicts the opponent's next shape from the last `order` shapes it played
+/// and plays the counter to it. Contexts not seen yet fall back to shorter
+/// ones, down to plain frequency counts for order 0.
+#[derive(Debug, Clone)]
+pub struct MarkovPlayer {
+    order: usize,
+    history: Vec<usize>,
+    /// Next shape counts for every context of up to `order` shapes
+    counts: BTreeMap<Vec<usize>, Vec<u32>>,
+}
+
+impl MarkovPlayer {
+    pub fn new(order: usize) -> Self {
+        Self { order, history: Vec::new(), counts: BTreeMap::new() }
+    }
+
+    /// The most likely next shape, the first one on ties. `None` before
+    /// anything has been observed.
+    pub fn predict(&self) -> Option<Shape> {
+        (0..=self.order.min(self.history.len()))
+            .rev()
+            .find_map(|length| self.counts.get(&self.history[self.history.len() - length..]))
+            .map(|counts| {
+                (0..counts.len())
+                    .rev()
+                    .max_by_key(|&shape| counts[shape])
+                    .map(Shape)
+                    .unwrap()
+            })
+    }
+}
+
+impl Player for MarkovPlayer {
+    fn name(&self) -> String {
+        format!("markov order {}", self.order)
+    }
+
+    fn choose(&mut self, _round: usize, rules: &Rules) -> Shape {
+        best_response(self.predict().unwrap_or(Shape::ROCK), rules)
+    }
+
+    fn observe(&mut self, opponent: Shape) {
+        for length in 0..=self.order.min(self.history.len()) {
+            let context = self.history[self.history.len() - length..].to_vec();
+            let counts = self.counts.entry(context).or_default();
+            if counts.len() <= opponent.index() {
+                counts.resize(opponent.index() + 1, 0);
+            }
+            counts[opponent.index()] += 1;
+        }
+        self.history.push(opponent.index());
+    }
+
+    fn reset(&mut self) {
+        self.history.clear();
+        self.counts.clear();
+    }
+}
+
+/// Scores of a player facing the opponent moves of a guide, and of the guide
+/// itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Replay {
+    pub rounds: u32,
+    pub player_score: u32,
+    pub guide_score: u32,
+    /// Rounds where the player's shape matched the one the guide plays
+    pub agreed: u32,
+}
+
+impl Replay {
+    /// How many points the player scored more than the guide, negative if
+    /// it did worse
+    pub fn advantage(&self) -> i64 {
+        self.player_score as i64 - self.guide_score as i64
+    }
+}
+
+/// Lets `player` play against the opponent column of `results` one round at
+/// a time, showing it each opponent shape only after it has chosen.
+pub fn replay(results: impl Iterator<Item=GameResult>, player: &mut dyn Player, rules: &Rules) -> Replay {
+    player.reset();
+    let mut replay = Replay { rounds: 0, player_score: 0, guide_score: 0, agreed: 0 };
+    for (round, result) in results.enumerate() {
+        let own = player.choose(round, rules);
+        player.observe(result.0);
+
+        replay.rounds += 1;
+        replay.player_score += GameResult(result.0, own).score(rules);
+        replay.guide_score += result.score(rules);
+        if own == result.1 {
+            replay.agreed += 1;
+        }
+    }
+    replay
+}
+
+/// Replays the guide against predictors of order 0 up to `max_order`.
+pub fn replay_orders(results: &[GameResult], max_order: usize, rules: &Rules) -> Vec<(usize, Replay)> {
+    (0..=max_order)
+        .map(|order| (order, replay(results.iter().copied(), &mut MarkovPlayer::new(order), rules)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day2::{GameResult, parse_scores, Rules, Shape};
+    use crate::day2::player::Player;
+    use crate::day2::predict::{MarkovPlayer, replay, replay_orders};
+
+    #[test]
+    fn test_predict_cycle() {
+        let rules = Rules::default();
+        let mut player = MarkovPlayer::new(1);
+        assert_eq!(player.predict(), None);
+        for shape in [Shape::ROCK, Shape::PAPER, Shape::SCISSORS, Shape::ROCK, Shape::PAPER] {
+            player.observe(shape);
+        }
+
+        // Order 1 has seen paper followed by scissors, order 0 would say rock
+        assert_eq!(player.predict(), Some(Shape::SCISSORS));
+        assert_eq!(player.choose(5, &rules), Shape::ROCK);
+        assert_eq!(MarkovPlayer::new(0).name(), "markov order 0");
+    }
+
+    #[test]
+    fn test_replay_repeating_opponent() {
+        let rules = Rules::default();
+        let results: Vec<GameResult> = (0..30)
+            .map(|round| GameResult(Shape(round % 3), Shape::ROCK))
+            .collect();
+        let orders = replay_orders(&results, 1, &rules);
+
+        assert_eq!(orders[1].1.guide_score, 30 + 10 * 3 + 10 * 6);
+        // Order 1 locks on to the cycle after one pass
+        assert!(orders[1].1.player_score > orders[0].1.player_score);
+        assert!(orders[1].1.player_score >= 27 * 8);
+        assert!(orders[1].1.advantage() > 0);
+    }
+
+    #[test]
+    fn test_replay_example() {
+        let example = include_str!("example.txt");
+        let rules = Rules::default();
+        let on_example = replay(parse_scores(example, &rules).unwrap(), &mut MarkovPlayer::new(2), &rules);
+
+        assert_eq!(
+            (on_example.rounds, on_example.guide_score),
+            (3, 15)
+        );
+        // Paper every round: against nothing seen, then against the rock
+        // seen so far, then against rock winning the tie with paper
+        assert_eq!(on_example.player_score, 8 + 5 + 2);
+        assert_eq!(on_example.agreed, 1);
+
+        let on_rocks = replay(parse_scores("A X\nA X\nA X\nA X\n", &rules).unwrap(), &mut MarkovPlayer::new(2), &rules);
+        assert_eq!(
+            (on_rocks.guide_score, on_rocks.player_score, on_rocks.advantage()),
+            (4 * 4, 4 * 8, 16)
+        );
+    }
+}
@@ -0,0 +1,182 @@
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use crate::day2::{GameResult, Outcome, parse_scores, parse_strategies, Rules, strategy_to_result};
+use crate::day2::infer::Reading;
+
+/// Round by round breakdown of a guide.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GuideStats {
+    /// Rounds per outcome, indexed by `Outcome::index`
+    pub outcomes: [u32; 3],
+    /// Rounds per own shape, indexed by `Shape::index`
+    pub own_shapes: Vec<u32>,
+    /// Points for the shapes played
+    pub shape_score: u32,
+    /// Points for the outcomes of the rounds
+    pub outcome_score: u32,
+    /// Total score after each round
+    pub running_totals: Vec<u32>,
+}
+
+/// Rock, Paper and Scissors, then Spock and Lizard for five shapes
+const SHAPE_NAMES: [&str; 5] = ["Rock", "Paper", "Scissors", "Spock", "Lizard"];
+
+impl GuideStats {
+    pub fn new(results: impl Iterator<Item=GameResult>, rules: &Rules) -> Self {
+        let mut stats = Self {
+            outcomes: [0; 3],
+            own_shapes: vec![0; rules.shapes()],
+            shape_score: 0,
+            outcome_score: 0,
+            running_totals: Vec::new(),
+        };
+        for result in results {
+            let outcome = result.outcome(rules);
+            stats.outcomes[outcome.index()] += 1;
+            stats.own_shapes[result.1.index()] += 1;
+            stats.shape_score += result.1.score(rules);
+            stats.outcome_score += outcome.score(rules);
+            stats.running_totals.push(stats.shape_score + stats.outcome_score);
+        }
+        stats
+    }
+
+    /// Statistics of `guide` read one way or the other
+    pub fn of_guide(guide: &str, rules: &Rules, reading: Reading) -> Result<Self, String> {
+        Ok(match reading {
+            Reading::Shapes => Self::new(parse_scores(guide, rules)?, rules),
+            Reading::Outcomes => Self::new(
+                parse_strategies(guide, rules)?.map(|strategy| strategy_to_result(strategy, rules)),
+                rules
+            ),
+        })
+    }
+
+    pub fn rounds(&self) -> usize {
+        self.running_totals.len()
+    }
+
+    pub fn total(&self) -> u32 {
+        self.shape_score + self.outcome_score
+    }
+
+    pub fn count(&self, outcome: Outcome) -> u32 {
+        self.outcomes[outcome.index()]
+    }
+
+    /// The running total once `percent` percent of the rounds are played
+    pub fn total_at(&self, percent: u8) -> u32 {
+        let rounds = self.rounds() * percent.min(100) as usize / 100;
+        rounds.checked_sub(1).map_or(0, |last| self.running_totals[last])
+    }
+}
+
+impl fmt::Display for GuideStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "rounds: {}", self.rounds())?;
+        writeln!(
+            f,
+            "wins/draws/losses: {}/{}/{}",
+            self.count(Outcome::Win),
+            self.count(Outcome::Draw),
+            self.count(Outcome::Loss)
+        )?;
+        write!(f, "own shapes:")?;
+        // By shape rather than by letter, since under `Reading::Outcomes`
+        // the letters mean outcomes
+        for (shape, count) in self.own_shapes.iter().enumerate() {
+            match SHAPE_NAMES.get(shape).filter(|_| self.own_shapes.len() <= SHAPE_NAMES.len()) {
+                Some(name) => write!(f, " {} {}", name, count)?,
+                None => write!(f, " shape {} {}", shape, count)?,
+            }
+        }
+        writeln!(f)?;
+        writeln!(
+            f,
+            "score: {} = {} from shapes + {} from outcomes",
+            self.total(),
+            self.shape_score,
+            self.outcome_score
+        )?;
+        writeln!(
+            f,
+            "running total at 25/50/75%: {}/{}/{}",
+            self.total_at(25),
+            self.total_at(50),
+            self.total_at(75)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day2::{Outcome, Rules};
+    use crate::day2::infer::Reading;
+    use crate::day2::stats::GuideStats;
+
+    #[test]
+    fn test_example_stats() {
+        let example = include_str!("example.txt");
+        let rules = Rules::default();
+        let shapes = GuideStats::of_guide(example, &rules, Reading::Shapes).unwrap();
+        let outcomes = GuideStats::of_guide(example, &rules, Reading::Outcomes).unwrap();
+
+        assert_eq!(shapes.outcomes, [1, 1, 1]);
+        assert_eq!(shapes.own_shapes, vec![1, 1, 1]);
+        assert_eq!((shapes.shape_score, shapes.outcome_score), (6, 9));
+        assert_eq!(shapes.running_totals, vec![8, 9, 15]);
+
+        assert_eq!(outcomes.own_shapes, vec![3, 0, 0]);
+        assert_eq!((outcomes.shape_score, outcomes.outcome_score), (3, 9));
+        assert_eq!(outcomes.running_totals, vec![4, 5, 12]);
+    }
+
+    #[test]
+    fn test_input_stats() {
+        let input = include_str!("input.txt");
+        let rules = Rules::default();
+        let shapes = GuideStats::of_guide(input, &rules, Reading::Shapes).unwrap();
+        let outcomes = GuideStats::of_guide(input, &rules, Reading::Outcomes).unwrap();
+
+        assert_eq!((shapes.total(), outcomes.total()), (15422, 15442));
+        assert_eq!(shapes.rounds(), 2500);
+        assert_eq!(
+            Outcome::ALL.iter().map(|&outcome| shapes.count(outcome)).sum::<u32>(),
+            2500
+        );
+        assert_eq!(shapes.total_at(100), 15422);
+        assert_eq!(shapes.total_at(0), 0);
+    }
+
+    #[test]
+    fn test_display() {
+        let stats = GuideStats::of_guide(include_str!("example.txt"), &Rules::default(), Reading::Shapes).unwrap();
+
+        assert_eq!(
+            stats.to_string(),
+            "rounds: 3\n\
+             wins/draws/losses: 1/1/1\n\
+             own shapes: Rock 1 Paper 1 Scissors 1\n\
+             score: 15 = 6 from shapes + 9 from outcomes\n\
+             running total at 25/50/75%: 0/8/9\n"
+        );
+    }
+
+    #[test]
+    fn test_display_outcomes_reading() {
+        let stats = GuideStats::of_guide(include_str!("example.txt"), &Rules::default(), Reading::Outcomes).unwrap();
+
+        assert_eq!(
+            stats.to_string(),
+            "rounds: 3\n\
+             wins/draws/losses: 1/1/1\n\
+             own shapes: Rock 3 Paper 0 Scissors 0\n\
+             score: 12 = 3 from shapes + 9 from outcomes\n\
+             running total at 25/50/75%: 0/4/5\n"
+        );
+        let seven = GuideStats::new(core::iter::empty(), &Rules::cyclic(7).unwrap());
+        assert!(seven.to_string().contains("own shapes: shape 0 0 shape 1 0"));
+    }
+}
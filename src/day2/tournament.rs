@@ -0,0 +1,285 @@
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+use crate::day2::{GameResult, Outcome, Rules};
+use crate::day2::player::Player;
+
+/// Match points for winning, drawing and losing a round robin match
+pub const WIN_POINTS: u32 = 3;
+pub const DRAW_POINTS: u32 = 1;
+
+/// Two players, by index in the tournament, and how their match went.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchResult {
+    pub players: (usize, usize),
+    pub scores: (u32, u32),
+    /// Rounds won by each player
+    pub wins: (u32, u32),
+    pub draws: u32,
+}
+
+impl MatchResult {
+    /// The player with the higher score, or with more rounds won if the
+    /// scores are level. `None` for a draw.
+    pub fn winner(&self) -> Option<usize> {
+        match self.scores.0.cmp(&self.scores.1).then(self.wins.0.cmp(&self.wins.1)) {
+            Ordering::Greater => Some(self.players.0),
+            Ordering::Less => Some(self.players.1),
+            Ordering::Equal => None,
+        }
+    }
+}
+
+/// Plays `rounds` rounds between two players, both starting from scratch.
+/// The result names them as players 0 and 1.
+pub fn play_match(first: &mut dyn Player, second: &mut dyn Player, rounds: usize, rules: &Rules) -> MatchResult {
+    first.reset();
+    second.reset();
+    let mut scores = (0, 0);
+    let mut wins = (0, 0);
+    let mut draws = 0;
+    for round in 0..rounds {
+        let a = first.choose(round, rules);
+        let b = second.choose(round, rules);
+        first.observe(b);
+        second.observe(a);
+
+        let result = GameResult(b, a);
+        scores.0 += result.score(rules);
+        scores.1 += GameResult(a, b).score(rules);
+        match result.outcome(rules) {
+            Outcome::Win => wins.0 += 1,
+            Outcome::Loss => wins.1 += 1,
+            Outcome::Draw => draws += 1,
+        }
+    }
+    MatchResult { players: (0, 1), scores, wins, draws }
+}
+
+/// One row of a round robin table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Standing {
+    pub player: usize,
+    pub name: String,
+    pub won: u32,
+    pub drawn: u32,
+    pub lost: u32,
+    pub points: u32,
+    /// Sum of round scores over all matches
+    pub score: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoundRobin {
+    pub matches: Vec<MatchResult>,
+    /// Most points first, then highest score, then entry order
+    pub standings: Vec<Standing>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Knockout {
+    pub names: Vec<String>,
+    /// Matches of each round, first round first. A player left without an
+    /// opponent goes through to the next round without playing.
+    pub rounds: Vec<Vec<MatchResult>>,
+    pub champion: Option<usize>,
+}
+
+/// Players and the rules and length of the matches they play.
+pub struct Tournament<'a> {
+    players: Vec<Box<dyn Player + 'a>>,
+    rules: Rules,
+    rounds: usize,
+}
+
+impl<'a> Tournament<'a> {
+    pub fn new(rules: Rules, rounds: usize) -> Self {
+        Self { players: Vec::new(), rules, rounds }
+    }
+
+    pub fn with_player(mut self, player: impl Player + 'a) -> Self {
+        self.players.push(Box::new(player));
+        self
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.players.iter().map(|player| player.name()).collect()
+    }
+
+    fn play(&mut self, first: usize, second: usize) -> MatchResult {
+        let (head, tail) = self.players.split_at_mut(second);
+        let result = play_match(head[first].as_mut(), tail[0].as_mut(), self.rounds, &self.rules);
+        MatchResult { players: (first, second), ..result }
+    }
+
+    /// Every player meets every other player once.
+    pub fn round_robin(&mut self) -> RoundRobin {
+        let names = self.names();
+        let mut standings: Vec<Standing> = names
+            .into_iter()
+            .enumerate()
+            .map(|(player, name)| Standing { player, name, won: 0, drawn: 0, lost: 0, points: 0, score: 0 })
+            .collect();
+        let mut matches = Vec::new();
+
+        for first in 0..self.players.len() {
+            for second in first + 1..self.players.len() {
+                let result = self.play(first, second);
+                standings[first].score += result.scores.0;
+                standings[second].score += result.scores.1;
+                match result.winner() {
+                    Some(winner) => {
+                        let loser = if winner == first { second } else { first };
+                        standings[winner].won += 1;
+                        standings[winner].points += WIN_POINTS;
+                        standings[loser].lost += 1;
+                    }
+                    None => {
+                        for player in [first, second] {
+                            standings[player].drawn += 1;
+                            standings[player].points += DRAW_POINTS;
+                        }
+                    }
+                }
+                matches.push(result);
+            }
+        }
+
+        standings.sort_by(|a, b| {
+            b.points.cmp(&a.points).then(b.score.cmp(&a.score)).then(a.player.cmp(&b.player))
+        });
+        RoundRobin { matches, standings }
+    }
+
+    /// Single elimination in entry order: the first player meets the second,
+    /// the third meets the fourth and so on. A drawn match goes to the player
+    /// who entered first.
+    pub fn knockout(&mut self) -> Knockout {
+        let mut remaining: Vec<usize> = (0..self.players.len()).collect();
+        let mut rounds = Vec::new();
+
+        while remaining.len() > 1 {
+            let mut matches = Vec::new();
+            let mut next = Vec::new();
+            for pair in remaining.chunks(2) {
+                if let [first, second] = *pair {
+                    let result = self.play(first, second);
+                    next.push(result.winner().unwrap_or(first));
+                    matches.push(result);
+                } else {
+                    next.push(pair[0]);
+                }
+            }
+            rounds.push(matches);
+            remaining = next;
+        }
+
+        Knockout { names: self.names(), rounds, champion: remaining.first().copied() }
+    }
+}
+
+impl fmt::Display for RoundRobin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = |player: usize| {
+            &self.standings.iter().find(|standing| standing.player == player).unwrap().name
+        };
+        for result in &self.matches {
+            writeln!(
+                f,
+                "{} {} - {} {}",
+                name(result.players.0), result.scores.0, result.scores.1, name(result.players.1)
+            )?;
+        }
+        writeln!(f)?;
+        for (rank, standing) in self.standings.iter().enumerate() {
+            writeln!(
+                f,
+                "{:>2}. {:<20} W{} D{} L{} {:>3} pts {:>6}",
+                rank + 1, standing.name, standing.won, standing.drawn, standing.lost, standing.points, standing.score
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Knockout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (round, matches) in self.rounds.iter().enumerate() {
+            writeln!(f, "round {}", round + 1)?;
+            for result in matches {
+                writeln!(
+                    f,
+                    "  {} {} - {} {}",
+                    self.names[result.players.0], result.scores.0, result.scores.1, self.names[result.players.1]
+                )?;
+            }
+        }
+        match self.champion {
+            Some(champion) => writeln!(f, "champion: {}", self.names[champion]),
+            None => writeln!(f, "no players"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day2::Rules;
+    use crate::day2::player::{AlwaysRock, FrequencyPlayer, GuidePlayer, RandomPlayer};
+    use crate::day2::tournament::{MatchResult, play_match, Tournament};
+
+    fn tournament(guide: &str) -> Tournament<'static> {
+        let rules = Rules::default();
+        Tournament::new(Rules::default(), 100)
+            .with_player(GuidePlayer::literal(guide, &rules).unwrap())
+            .with_player(GuidePlayer::outcome_driven(guide, &rules).unwrap())
+            .with_player(AlwaysRock)
+            .with_player(RandomPlayer::new(42))
+            .with_player(FrequencyPlayer::new())
+    }
+
+    #[test]
+    fn test_round_robin() {
+        let result = tournament(include_str!("example.txt")).round_robin();
+
+        assert_eq!(result.matches.len(), 10);
+        assert_eq!(result.standings.len(), 5);
+        // The outcome guide plays nothing but rock against anyone
+        assert_eq!(
+            result.matches[4],
+            MatchResult { players: (1, 2), scores: (400, 400), wins: (0, 0), draws: 100 }
+        );
+        // Countering the most common shape beats always rock every round
+        assert_eq!(
+            result.matches[8].wins,
+            (0, 100)
+        );
+        let frequency = result.standings.iter().find(|standing| standing.player == 4).unwrap();
+        assert!(frequency.won >= 2);
+    }
+
+    #[test]
+    fn test_play_match() {
+        let rules = Rules::default();
+        let result = play_match(&mut FrequencyPlayer::new(), &mut AlwaysRock, 10, &rules);
+
+        assert_eq!(
+            result,
+            MatchResult { players: (0, 1), scores: (80, 10), wins: (10, 0), draws: 0 }
+        );
+        assert_eq!(result.winner(), Some(0));
+    }
+
+    #[test]
+    fn test_knockout() {
+        let result = tournament(include_str!("example.txt")).knockout();
+
+        assert_eq!(
+            result.rounds.iter().map(Vec::len).collect::<Vec<usize>>(),
+            vec![2, 1, 1]
+        );
+        assert!(result.champion.is_some());
+        assert!(result.to_string().ends_with(&format!("champion: {}\n", result.names[result.champion.unwrap()])));
+    }
+}
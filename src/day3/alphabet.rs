@@ -0,0 +1,99 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use crate::day3::items::ItemSet;
+
+const NOT_AN_ITEM: u8 = u8::MAX;
+
+/// The items rucksacks can hold, in priority order: the first item has
+/// priority 1, the second 2 and so on. At most 64 items, so that any set of
+/// them fits an `ItemSet`, where each item is the bit of its index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alphabet {
+    items: Vec<char>,
+    /// Index of each ASCII item, `NOT_AN_ITEM` for characters that are not one
+    ascii: [u8; 128],
+}
+
+impl Default for Alphabet {
+    /// The puzzle's `a-z` followed by `A-Z`
+    fn default() -> Self {
+        Self::new("abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ").unwrap()
+    }
+}
+
+impl Alphabet {
+    pub fn new(items: &str) -> Result<Self, String> {
+        let items: Vec<char> = items.chars().collect();
+        if items.len() > 64 {
+            return Err("An alphabet has at most 64 items".to_string());
+        }
+        if items.iter().enumerate().any(|(idx, item)| items[..idx].contains(item)) {
+            return Err("Alphabet items have to be distinct".to_string());
+        }
+
+        let mut ascii = [NOT_AN_ITEM; 128];
+        for (idx, &item) in items.iter().enumerate() {
+            if item.is_ascii() {
+                ascii[item as usize] = idx as u8;
+            }
+        }
+        Ok(Self { items, ascii })
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Position of `item` in the alphabet, `None` if it is not in it
+    pub fn index(&self, item: char) -> Option<u32> {
+        if item.is_ascii() {
+            Some(self.ascii[item as usize]).filter(|&idx| idx != NOT_AN_ITEM).map(u32::from)
+        } else {
+            self.items.iter().position(|&c| c == item).map(|idx| idx as u32)
+        }
+    }
+
+    pub fn priority(&self, item: char) -> Option<u32> {
+        self.index(item).map(|idx| idx + 1)
+    }
+
+    /// The items of `set`, in priority order
+    pub fn items(&self, set: ItemSet) -> impl Iterator<Item=char> + '_ {
+        set.indices().filter_map(|idx| self.items.get(idx as usize).copied())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day3::alphabet::Alphabet;
+    use crate::day3::items::ItemSet;
+
+    #[test]
+    fn test_default_priorities() {
+        let alphabet = Alphabet::default();
+
+        assert_eq!(alphabet.priority('p'), Some(16));
+        assert_eq!(alphabet.priority('L'), Some(38));
+        assert_eq!(alphabet.priority('1'), None);
+        assert_eq!(alphabet.priority('ä'), None);
+        // The default alphabet puts items on the same bits as `ItemSet`'s
+        // own letters
+        let set: ItemSet = "pLz".chars().collect();
+        assert_eq!(alphabet.items(set).collect::<String>(), "pzL");
+    }
+
+    #[test]
+    fn test_custom_alphabet() {
+        let alphabet = Alphabet::new("0123456789äö").unwrap();
+
+        assert_eq!(alphabet.priority('0'), Some(1));
+        assert_eq!(alphabet.priority('ö'), Some(12));
+        assert_eq!(alphabet.priority('a'), None);
+        assert!(Alphabet::new("abca").is_err());
+        assert!(Alphabet::new(&"x".repeat(65)).is_err());
+    }
+}
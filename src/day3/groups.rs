@@ -0,0 +1,186 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use crate::cancel::{CancellationToken, Cancelled};
+use crate::day3::alphabet::Alphabet;
+use crate::day3::items::ItemSet;
+use crate::day3::valid_rucksacks;
+
+/// Three rucksacks, by index in the list searched, and the one item they
+/// all carry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Group {
+    pub rucksacks: [usize; 3],
+    pub badge: char,
+}
+
+/// Every item of each rucksack in `file`, in the order listed, skipping
+/// lines that are not rucksacks
+pub fn rucksack_items(file: &str) -> Vec<ItemSet> {
+    let alphabet = Alphabet::default();
+    valid_rucksacks(file, &alphabet).map(|(_, r)| r.all_items()).collect()
+}
+
+/// Splits the rucksacks into groups of three with exactly one common item
+/// each, whatever order they come in. `None` if there is no such split.
+///
+/// Tries the rucksack with the fewest possible groups left first, so a
+/// rucksack that fits nowhere ends the branch early.
+pub fn find_groups(rucksacks: &[ItemSet], cancel: &CancellationToken) -> Result<Option<Vec<Group>>, Cancelled> {
+    if !rucksacks.len().is_multiple_of(3) {
+        return Ok(None);
+    }
+
+    let mut candidates = Vec::new();
+    let mut by_rucksack = vec![Vec::new(); rucksacks.len()];
+    for a in 0..rucksacks.len() {
+        for b in a + 1..rucksacks.len() {
+            let ab = rucksacks[a] & rucksacks[b];
+            if ab.is_empty() {
+                continue;
+            }
+            for (c, &items) in rucksacks.iter().enumerate().skip(b + 1) {
+                let common = ab & items;
+                if common.len() == 1 {
+                    for rucksack in [a, b, c] {
+                        by_rucksack[rucksack].push(candidates.len());
+                    }
+                    candidates.push(Group { rucksacks: [a, b, c], badge: common.first().unwrap() });
+                }
+            }
+        }
+        cancel.check()?;
+    }
+
+    let mut search = Search {
+        candidates: &candidates,
+        by_rucksack: &by_rucksack,
+        grouped: vec![false; rucksacks.len()],
+        chosen: Vec::with_capacity(rucksacks.len() / 3),
+        cancel,
+        nodes: 0,
+    };
+    if search.branch()? {
+        Ok(Some(search.chosen.iter().map(|&candidate| candidates[candidate]).collect()))
+    } else {
+        Ok(None)
+    }
+}
+
+struct Search<'a> {
+    candidates: &'a [Group],
+    /// Indices into `candidates` of the groups each rucksack could be in
+    by_rucksack: &'a [Vec<usize>],
+    grouped: Vec<bool>,
+    chosen: Vec<usize>,
+    cancel: &'a CancellationToken,
+    nodes: u64,
+}
+
+impl Search<'_> {
+    fn open(&self, rucksack: usize) -> impl Iterator<Item=usize> + '_ {
+        self.by_rucksack[rucksack]
+            .iter()
+            .copied()
+            .filter(|&candidate| {
+                self.candidates[candidate].rucksacks.iter().all(|&r| !self.grouped[r])
+            })
+    }
+
+    /// True once every rucksack is grouped, with the groups in `chosen`
+    fn branch(&mut self) -> Result<bool, Cancelled> {
+        if self.nodes.is_multiple_of(4096) {
+            self.cancel.check()?;
+        }
+        self.nodes += 1;
+
+        let mut next = None;
+        let mut fewest = usize::MAX;
+        for rucksack in (0..self.grouped.len()).filter(|&rucksack| !self.grouped[rucksack]) {
+            let open = self.open(rucksack).take(fewest).count();
+            if open < fewest {
+                (next, fewest) = (Some(rucksack), open);
+            }
+            // Nothing beats a rucksack with one way left, or none
+            if fewest <= 1 {
+                break;
+            }
+        }
+        let Some(next) = next else {
+            return Ok(true);
+        };
+
+        let open: Vec<usize> = self.open(next).collect();
+        for candidate in open {
+            for &r in &self.candidates[candidate].rucksacks {
+                self.grouped[r] = true;
+            }
+            self.chosen.push(candidate);
+
+            if self.branch()? {
+                return Ok(true);
+            }
+
+            self.chosen.pop();
+            for &r in &self.candidates[candidate].rucksacks {
+                self.grouped[r] = false;
+            }
+        }
+
+        Ok(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cancel::{CancellationToken, Cancelled};
+    use crate::day3::groups::{find_groups, Group, rucksack_items};
+    use crate::day3::items::ItemSet;
+    use crate::rng::Rng;
+
+    fn assert_valid(rucksacks: &[ItemSet], groups: &[Group]) {
+        let mut seen = vec![false; rucksacks.len()];
+        for group in groups {
+            let [a, b, c] = group.rucksacks;
+            let common = rucksacks[a] & rucksacks[b] & rucksacks[c];
+            assert_eq!(common.iter().collect::<Vec<char>>(), vec![group.badge]);
+            for r in group.rucksacks {
+                assert!(!seen[r]);
+                seen[r] = true;
+            }
+        }
+        assert!(seen.iter().all(|&s| s));
+    }
+
+    #[test]
+    fn test_shuffled_example() {
+        let mut rucksacks = rucksack_items(include_str!("example.txt"));
+        Rng::new(3).shuffle(&mut rucksacks);
+        let groups = find_groups(&rucksacks, &CancellationToken::new()).unwrap().unwrap();
+
+        assert_eq!(groups.len(), 2);
+        assert_valid(&rucksacks, &groups);
+    }
+
+    #[test]
+    fn test_shuffled_input() {
+        let mut rucksacks = rucksack_items(include_str!("input.txt"));
+        Rng::new(2022).shuffle(&mut rucksacks);
+        let groups = find_groups(&rucksacks, &CancellationToken::new()).unwrap().unwrap();
+
+        assert_eq!(groups.len(), 100);
+        assert_valid(&rucksacks, &groups);
+    }
+
+    #[test]
+    fn test_no_grouping() {
+        let cancel = CancellationToken::new();
+        let items = |s: &str| s.chars().collect::<ItemSet>();
+
+        assert_eq!(find_groups(&[items("ab"), items("ab"), items("ab")], &cancel), Ok(None));
+        assert_eq!(find_groups(&[items("a"), items("a")], &cancel), Ok(None));
+        assert_eq!(find_groups(&[], &cancel), Ok(Some(vec![])));
+
+        cancel.cancel();
+        assert_eq!(find_groups(&[items("a"), items("a"), items("a")], &cancel), Err(Cancelled));
+    }
+}
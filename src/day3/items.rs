@@ -0,0 +1,144 @@
+use core::ops::{BitAnd, BitOr};
+
+/// A set of rucksack items, one bit per item. An item's bit is its index in
+/// the `Alphabet`, so iterating goes in priority order. The `char` methods
+/// use the letters of the default alphabet, `a-z` and `A-Z`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ItemSet(u64);
+
+impl ItemSet {
+    /// Every letter of the default alphabet
+    pub const ALL: ItemSet = ItemSet((1 << 52) - 1);
+
+    pub fn new() -> Self {
+        Self(0)
+    }
+
+    /// The bit of `item`, `None` if it is not a letter
+    fn bit(item: char) -> Option<u32> {
+        match item {
+            'a'..='z' => Some(item as u32 - 'a' as u32),
+            'A'..='Z' => Some(item as u32 - 'A' as u32 + 26),
+            _ => None,
+        }
+    }
+
+    /// Returns false if `item` is not a letter and cannot be stored
+    pub fn insert(&mut self, item: char) -> bool {
+        match Self::bit(item) {
+            Some(bit) => {
+                self.0 |= 1 << bit;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn contains(&self, item: char) -> bool {
+        Self::bit(item).is_some_and(|bit| self.0 & (1 << bit) != 0)
+    }
+
+    pub fn intersection(self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    pub fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// The item with the lowest priority
+    pub fn first(&self) -> Option<char> {
+        self.iter().next()
+    }
+
+    /// Adds the item at `index` of an `Alphabet`
+    pub fn insert_index(&mut self, index: u32) {
+        self.0 |= 1 << index;
+    }
+
+    /// Bit positions of the items, lowest first
+    pub fn indices(&self) -> impl Iterator<Item=u32> {
+        let mut bits = self.0;
+        core::iter::from_fn(move || {
+            if bits == 0 {
+                return None;
+            }
+            let bit = bits.trailing_zeros();
+            bits &= bits - 1;
+            Some(bit)
+        })
+    }
+
+    /// The items as letters, which only makes sense for sets of the
+    /// default `Alphabet`
+    pub fn iter(&self) -> impl Iterator<Item=char> {
+        self.indices().filter(|&bit| bit < 52).map(|bit| {
+            if bit < 26 {
+                (b'a' + bit as u8) as char
+            } else {
+                (b'A' + (bit - 26) as u8) as char
+            }
+        })
+    }
+}
+
+impl BitAnd for ItemSet {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        self.intersection(rhs)
+    }
+}
+
+impl BitOr for ItemSet {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        self.union(rhs)
+    }
+}
+
+impl FromIterator<char> for ItemSet {
+    /// Skips anything that is not a letter
+    fn from_iter<I: IntoIterator<Item=char>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for item in iter {
+            set.insert(item);
+        }
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day3::items::ItemSet;
+
+    #[test]
+    fn test_set_operations() {
+        let a: ItemSet = "vJrwpWtwJgWr".chars().collect();
+        let b: ItemSet = "hcsFMMfFFhFp".chars().collect();
+
+        assert_eq!((a & b).iter().collect::<Vec<char>>(), vec!['p']);
+        assert_eq!((a | b).len(), 14);
+        assert!(a.contains('W') && !a.contains('P'));
+        assert_eq!(ItemSet::ALL.len(), 52);
+        assert!(ItemSet::new().is_empty());
+    }
+
+    #[test]
+    fn test_iterates_in_priority_order() {
+        let set: ItemSet = "ZaAz!".chars().collect();
+
+        assert_eq!(set.iter().collect::<String>(), "azAZ");
+        assert_eq!(set.first(), Some('a'));
+        assert!(!set.contains('!'));
+    }
+}
@@ -1,79 +1,311 @@
-use std::collections::HashSet;
-use std::str::FromStr;
+pub mod alphabet;
+pub mod groups;
+pub mod items;
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use crate::day3::alphabet::Alphabet;
+use crate::day3::items::ItemSet;
+use crate::normalize;
 
 struct Rucksack {
-    left: HashSet<char>,
-    right: HashSet<char>,
+    left: ItemSet,
+    right: ItemSet,
 }
 
 impl Rucksack {
-    // Assumes zero to one
-    fn common_item_in_compartments(&self) -> char {
-        // Unwrap ok due to invariant
-        *self.left.intersection(&self.right).take(1).next().unwrap()
+    /// Every character has to be an item of `alphabet`, and there has to be
+    /// an even number of them to fill the two compartments
+    fn parse(s: &str, line: usize, alphabet: &Alphabet) -> Result<Self, RucksackError> {
+        let items = s
+            .chars()
+            .enumerate()
+            .map(|(idx, item)| {
+                alphabet.index(item).ok_or(RucksackError::IllegalItem { line, column: idx + 1, item })
+            })
+            .collect::<Result<Vec<u32>, RucksackError>>()?;
+        if !items.len().is_multiple_of(2) {
+            return Err(RucksackError::OddLength { line, length: items.len() });
+        }
+
+        let (left, right) = items.split_at(items.len() / 2);
+        let set = |items: &[u32]| {
+            let mut set = ItemSet::new();
+            for &idx in items {
+                set.insert_index(idx);
+            }
+            set
+        };
+        Ok(Self { left: set(left), right: set(right) })
+    }
+
+    fn common_items_in_compartments(&self) -> ItemSet {
+        self.left & self.right
+    }
+
+    fn all_items(&self) -> ItemSet {
+        self.left | self.right
+    }
+}
+
+/// A line that is not a rucksack. `line` and `column` are 1-based, columns
+/// counting characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RucksackError {
+    /// The items cannot be split evenly between the compartments
+    OddLength { line: usize, length: usize },
+    /// A character that is not in the alphabet
+    IllegalItem { line: usize, column: usize, item: char },
+}
+
+impl fmt::Display for RucksackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RucksackError::OddLength { line, length } => {
+                write!(f, "line {}: {} items do not split into two compartments", line, length)
+            }
+            RucksackError::IllegalItem { line, column, item } => {
+                write!(f, "line {}, column {}: {:?} is not an item", line, column, item)
+            }
+        }
+    }
+}
+
+/// Sum of the priorities of the items in `set`
+fn priorities(set: ItemSet) -> u32 {
+    set.indices().map(|idx| idx + 1).sum()
+}
+
+/// What the totals do when a rucksack, or a group for badges, does not
+/// share exactly one item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SharedPolicy {
+    /// Stop with a `SharedItemError`
+    Error,
+    /// Leave it out of the total
+    Skip,
+    /// Add up the priorities of all shared items, none counting as zero
+    SumAll,
+}
+
+/// A rucksack or group not sharing exactly one item. `line` is 1-based and
+/// for a group is the line of its first rucksack.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SharedItemError {
+    pub line: usize,
+    /// The shared items in priority order
+    pub shared: String,
+}
+
+impl fmt::Display for SharedItemError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: expected one shared item, found {}", self.line, self.shared.chars().count())?;
+        if !self.shared.is_empty() {
+            write!(f, " ({})", self.shared)?;
+        }
+        Ok(())
     }
+}
+
+/// Why a day3 total could not be added up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TotalError {
+    Rucksack(RucksackError),
+    Shared(SharedItemError),
+    /// The last group starting at 1-based `line` has only `rucksacks` of
+    /// the `group_size` it should have
+    Incomplete { line: usize, rucksacks: usize, group_size: usize },
+    ZeroGroupSize,
+}
 
-    fn all_items(&self) -> HashSet<char> {
-        self.left.union(&self.right).cloned().collect()
+impl From<RucksackError> for TotalError {
+    fn from(error: RucksackError) -> Self {
+        TotalError::Rucksack(error)
     }
 }
 
-impl FromStr for Rucksack {
-    type Err = String;
+impl From<SharedItemError> for TotalError {
+    fn from(error: SharedItemError) -> Self {
+        TotalError::Shared(error)
+    }
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Self {
-            left: s.chars().take(s.len()/2).collect(),
-            right: s.chars().rev().take(s.len()/2).collect(),
-        })
+impl fmt::Display for TotalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TotalError::Rucksack(error) => write!(f, "{}", error),
+            TotalError::Shared(error) => write!(f, "{}", error),
+            TotalError::Incomplete { line, rucksacks, group_size } => write!(
+                f,
+                "line {}: last group has {} rucksacks, expected {}",
+                line, rucksacks, group_size
+            ),
+            TotalError::ZeroGroupSize => write!(f, "groups need at least one rucksack"),
+        }
     }
 }
 
-fn priority(c: char) -> u32 {
-    if c.is_uppercase() {
-        c as u32 - 'A' as u32 + 27
-    } else {
-        c as u32 - 'a' as u32 + 1
+/// Priority of the one shared item, or whatever `policy` makes of anything else
+fn shared_priority(line: usize, shared: ItemSet, policy: SharedPolicy, alphabet: &Alphabet) -> Result<u32, SharedItemError> {
+    match (shared.len(), policy) {
+        (1, _) | (_, SharedPolicy::SumAll) => Ok(priorities(shared)),
+        (_, SharedPolicy::Skip) => Ok(0),
+        (_, SharedPolicy::Error) => Err(SharedItemError { line, shared: alphabet.items(shared).collect() }),
     }
 }
 
-fn sum_priorities(file: &str) -> u32 {
-    file
-        .lines()
-        .filter_map(|line| line.parse::<Rucksack>().ok())
-        .map(|r| priority(r.common_item_in_compartments()))
-        .sum()
+/// Rucksacks by 1-based line number, skipping blank lines
+fn parse_rucksacks<'a>(file: &'a str, alphabet: &'a Alphabet) -> impl Iterator<Item=Result<(usize, Rucksack), RucksackError>> + 'a {
+    normalize::lines(file)
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty())
+        .map(move |(idx, line)| Rucksack::parse(line, idx + 1, alphabet).map(|r| (idx + 1, r)))
+}
+
+/// Like `parse_rucksacks`, skipping lines that are not rucksacks
+fn valid_rucksacks<'a>(file: &'a str, alphabet: &'a Alphabet) -> impl Iterator<Item=(usize, Rucksack)> + 'a {
+    parse_rucksacks(file, alphabet).filter_map(Result::ok)
+}
+
+/// Checks that every line that is not blank is a rucksack, returning how
+/// many there are
+pub fn validate(file: &str, alphabet: &Alphabet) -> Result<usize, RucksackError> {
+    parse_rucksacks(file, alphabet).try_fold(0, |count, r| r.map(|_| count + 1))
+}
+
+/// The items in both compartments of every rucksack, by line number,
+/// skipping lines that are not rucksacks
+pub fn shared_items(file: &str) -> impl Iterator<Item=(usize, ItemSet)> + '_ {
+    let alphabet = Alphabet::default();
+    normalize::lines(file)
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty())
+        .filter_map(move |(idx, line)| Rucksack::parse(line, idx + 1, &alphabet).ok().map(|r| (idx + 1, r)))
+        .map(|(line, r)| (line, r.common_items_in_compartments()))
+}
+
+/// How many rucksacks have each item in both compartments
+pub fn shared_item_counts(file: &str) -> BTreeMap<char, usize> {
+    let mut counts = BTreeMap::new();
+    for (_, shared) in shared_items(file) {
+        for item in shared.iter() {
+            *counts.entry(item).or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
+fn total(
+    rucksacks: impl Iterator<Item=Result<(usize, Rucksack), RucksackError>>,
+    alphabet: &Alphabet,
+    policy: SharedPolicy,
+) -> Result<u32, TotalError> {
+    let mut sum = 0;
+    for r in rucksacks {
+        let (line, r) = r?;
+        sum += shared_priority(line, r.common_items_in_compartments(), policy, alphabet)?;
+    }
+    Ok(sum)
+}
+
+/// Sum of the priorities of the items in both compartments, skipping lines
+/// that are not rucksacks. Rucksacks are supposed to share exactly one item,
+/// those that do not are added up as in `SharedPolicy::SumAll`.
+pub fn sum_priorities(file: &str) -> u32 {
+    let alphabet = Alphabet::default();
+    total(valid_rucksacks(file, &alphabet).map(Ok), &alphabet, SharedPolicy::SumAll).unwrap()
+}
+
+/// Like `sum_priorities` with items and priorities from `alphabet`, failing
+/// on the first line that is not a rucksack
+pub fn sum_priorities_with(file: &str, alphabet: &Alphabet, policy: SharedPolicy) -> Result<u32, TotalError> {
+    total(parse_rucksacks(file, alphabet), alphabet, policy)
 }
 
 // Part two
 
-fn common_in_three(a: &Rucksack, b: &Rucksack, c: &Rucksack) -> char  {
-    let ab: HashSet<char> = a.all_items().intersection(&b.all_items()).cloned().collect();
-    *ab.intersection(&c.all_items()).take(1).next().unwrap()
+/// Items every rucksack of the group carries
+fn common_in_group(group: &[Rucksack]) -> ItemSet {
+    group
+        .iter()
+        .map(Rucksack::all_items)
+        .reduce(ItemSet::intersection)
+        .unwrap_or_default()
 }
 
-fn sum_group_priorities(file: &str) -> u32 {
-    let mut rucksacks = file
-        .lines()
-        .filter_map(|line| line.parse::<Rucksack>().ok())
-        .peekable();
+fn group_total(
+    rucksacks: impl Iterator<Item=Result<(usize, Rucksack), RucksackError>>,
+    alphabet: &Alphabet,
+    group_size: usize,
+    policy: SharedPolicy,
+) -> Result<u32, TotalError> {
+    if group_size == 0 {
+        return Err(TotalError::ZeroGroupSize);
+    }
 
     let mut sum: u32 = 0;
+    let mut group = Vec::with_capacity(group_size);
+    let mut first_line = 0;
 
-    while rucksacks.peek().is_some() {
-        sum += priority(common_in_three(
-        &rucksacks.next().unwrap(),
-        &rucksacks.next().unwrap(),
-        &rucksacks.next().unwrap(),
-        ));
+    for r in rucksacks {
+        let (line, r) = r?;
+        if group.is_empty() {
+            first_line = line;
+        }
+        group.push(r);
+        if group.len() == group_size {
+            sum += shared_priority(first_line, common_in_group(&group), policy, alphabet)?;
+            group.clear();
+        }
     }
 
-    sum
+    if !group.is_empty() {
+        return Err(TotalError::Incomplete { line: first_line, rucksacks: group.len(), group_size });
+    }
+
+    Ok(sum)
+}
+
+/// Sum of the badge priorities of each group of three, skipping lines that
+/// are not rucksacks. Groups not sharing exactly one item are added up as in
+/// `SharedPolicy::SumAll`.
+pub fn sum_group_priorities(file: &str) -> Result<u32, TotalError> {
+    let alphabet = Alphabet::default();
+    group_total(valid_rucksacks(file, &alphabet).map(Ok), &alphabet, 3, SharedPolicy::SumAll)
+}
+
+/// Sum of the badge priorities of each group of `group_size` consecutive
+/// rucksacks, with items and priorities from `alphabet`. Fails on the first
+/// line that is not a rucksack.
+pub fn sum_group_priorities_with(file: &str, alphabet: &Alphabet, group_size: usize, policy: SharedPolicy) -> Result<u32, TotalError> {
+    group_total(parse_rucksacks(file, alphabet), alphabet, group_size, policy)
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::day3::{sum_group_priorities, sum_priorities};
+    use std::collections::HashSet;
+    use test::Bencher;
+    use crate::day3::{RucksackError, shared_item_counts, shared_items, SharedItemError, SharedPolicy, sum_group_priorities, sum_group_priorities_with, sum_priorities, sum_priorities_with, TotalError, validate};
+    use crate::day3::alphabet::Alphabet;
+
+    /// The set based solution `Rucksack` had before `ItemSet`, to bench against
+    fn sum_priorities_hashset(file: &str) -> u32 {
+        file
+            .lines()
+            .map(|line| {
+                let left: HashSet<char> = line.chars().take(line.len() / 2).collect();
+                let right: HashSet<char> = line.chars().rev().take(line.len() / 2).collect();
+                let c = *left.intersection(&right).next().unwrap();
+                if c.is_uppercase() {
+                    c as u32 - 'A' as u32 + 27
+                } else {
+                    c as u32 - 'a' as u32 + 1
+                }
+            })
+            .sum()
+    }
 
     #[test]
     fn test_example1() {
@@ -101,7 +333,7 @@ mod tests {
 
         assert_eq!(
             sum_group_priorities(input),
-            70
+            Ok(70)
         )
     }
 
@@ -111,7 +343,126 @@ mod tests {
 
         assert_eq!(
             sum_group_priorities(input),
-            2683
+            Ok(2683)
         )
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_matches_hashset() {
+        let input = include_str!("input.txt");
+
+        assert_eq!(
+            sum_priorities(input),
+            sum_priorities_hashset(input)
+        )
+    }
+
+    #[test]
+    fn test_shared_items() {
+        let input = "abca\nabcd\nabab\n";
+
+        assert_eq!(
+            shared_items(input).map(|(line, shared)| (line, shared.iter().collect::<String>())).collect::<Vec<_>>(),
+            vec![(1, "a".to_string()), (2, "".to_string()), (3, "ab".to_string())]
+        );
+        assert_eq!(
+            shared_item_counts(input).into_iter().collect::<Vec<_>>(),
+            vec![('a', 2), ('b', 1)]
+        );
+    }
+
+    #[test]
+    fn test_shared_policies() {
+        let input = "abca\nabcd\nabab\n";
+        let alphabet = Alphabet::default();
+
+        assert_eq!(sum_priorities_with(input, &alphabet, SharedPolicy::SumAll), Ok(1 + 1 + 2));
+        assert_eq!(sum_priorities_with(input, &alphabet, SharedPolicy::Skip), Ok(1));
+        assert_eq!(
+            sum_priorities_with(input, &alphabet, SharedPolicy::Error),
+            Err(TotalError::Shared(SharedItemError { line: 2, shared: String::new() }))
+        );
+        assert_eq!(sum_priorities(input), 4);
+        assert_eq!(
+            sum_group_priorities_with("aBcd\nBacd\ncdaB\n", &alphabet, 3, SharedPolicy::Error).unwrap_err().to_string(),
+            "line 1: expected one shared item, found 4 (acdB)"
+        );
+        assert_eq!(sum_group_priorities_with(include_str!("input.txt"), &alphabet, 3, SharedPolicy::Error), Ok(2683));
+    }
+
+    #[test]
+    fn test_group_sizes() {
+        let input = include_str!("example.txt");
+        let alphabet = Alphabet::default();
+
+        assert_eq!(sum_group_priorities_with(input, &alphabet, 3, SharedPolicy::Error), Ok(70));
+        assert_eq!(
+            sum_group_priorities_with(input, &alphabet, 6, SharedPolicy::Error),
+            Err(TotalError::Shared(SharedItemError { line: 1, shared: String::new() }))
+        );
+        assert_eq!(sum_group_priorities_with(input, &alphabet, 1, SharedPolicy::Skip), Ok(0));
+        assert_eq!(
+            sum_group_priorities_with(input, &alphabet, 4, SharedPolicy::SumAll),
+            Err(TotalError::Incomplete { line: 5, rucksacks: 2, group_size: 4 })
+        );
+        assert_eq!(
+            sum_group_priorities_with(input, &alphabet, 4, SharedPolicy::SumAll).unwrap_err().to_string(),
+            "line 5: last group has 2 rucksacks, expected 4"
+        );
+        assert_eq!(sum_group_priorities_with(input, &alphabet, 0, SharedPolicy::SumAll), Err(TotalError::ZeroGroupSize));
+        assert_eq!(
+            sum_group_priorities("abab\nabab\n"),
+            Err(TotalError::Incomplete { line: 1, rucksacks: 2, group_size: 3 })
+        );
+    }
+
+    #[test]
+    fn test_validation() {
+        let alphabet = Alphabet::default();
+
+        assert_eq!(validate(include_str!("input.txt"), &alphabet), Ok(300));
+        assert_eq!(
+            validate("abab\nabcab\n", &alphabet),
+            Err(RucksackError::OddLength { line: 2, length: 5 })
+        );
+        assert_eq!(
+            validate("abab\n\nab1b\n", &alphabet).unwrap_err().to_string(),
+            "line 3, column 3: '1' is not an item"
+        );
+        assert_eq!(
+            sum_priorities_with("abab\naäaä\n", &alphabet, SharedPolicy::SumAll),
+            Err(TotalError::Rucksack(RucksackError::IllegalItem { line: 2, column: 2, item: 'ä' }))
+        );
+        // The lenient totals skip what is not a rucksack
+        assert_eq!(sum_priorities("abca\nabcab\n12\n"), 1);
+    }
+
+    #[test]
+    fn test_custom_alphabet() {
+        let alphabet = Alphabet::new("0123456789").unwrap();
+
+        assert_eq!(sum_priorities_with("1213\n9089\n", &alphabet, SharedPolicy::Error), Ok(2 + 10));
+        assert_eq!(
+            sum_group_priorities_with("1213\n9119\n5115\n", &alphabet, 3, SharedPolicy::Error),
+            Ok(2)
+        );
+        assert_eq!(
+            sum_priorities_with("1234\n", &alphabet, SharedPolicy::Error).unwrap_err().to_string(),
+            "line 1: expected one shared item, found 0"
+        );
+    }
+
+    #[bench]
+    fn bench_input1(b: &mut Bencher) {
+        let input = include_str!("input.txt");
+
+        b.iter(|| sum_priorities(input));
+    }
+
+    #[bench]
+    fn bench_input1_hashset(b: &mut Bencher) {
+        let input = include_str!("input.txt");
+
+        b.iter(|| sum_priorities_hashset(input));
+    }
+}
@@ -1,7 +1,9 @@
-use std::collections::HashSet;
-use std::str::FromStr;
+use alloc::collections::BTreeSet;
+use alloc::string::String;
+use core::str::FromStr;
+use crate::normalize;
 
-struct ElfPair(HashSet<u32>, HashSet<u32>);
+pub struct ElfPair(BTreeSet<u32>, BTreeSet<u32>);
 
 impl FromStr for ElfPair {
     type Err = String;
@@ -35,18 +37,17 @@ impl ElfPair {
     }
 }
 
-fn parse_elfs(file: &str) -> impl Iterator<Item=ElfPair> + '_ {
-    file
-        .lines()
+pub fn parse_elfs(file: &str) -> impl Iterator<Item=ElfPair> + '_ {
+    normalize::lines(file)
         .filter_map(|line| line.parse::<ElfPair>().ok())
 }
 
-fn pairs_with_fully_contained_assignments(elfs: impl Iterator<Item=ElfPair>) -> impl Iterator<Item=ElfPair> {
+pub fn pairs_with_fully_contained_assignments(elfs: impl Iterator<Item=ElfPair>) -> impl Iterator<Item=ElfPair> {
     elfs
         .filter(ElfPair::one_contains_other)
 }
 
-fn pairs_with_overlap(elfs: impl Iterator<Item=ElfPair>) -> impl Iterator<Item=ElfPair> {
+pub fn pairs_with_overlap(elfs: impl Iterator<Item=ElfPair>) -> impl Iterator<Item=ElfPair> {
     elfs
         .filter(ElfPair::is_overlapping)
 }
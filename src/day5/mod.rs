@@ -1,10 +1,15 @@
-use std::str::FromStr;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::str::FromStr;
+use crate::cancel::{CancellationToken, Cancelled};
+use crate::normalize::{self, Normalizer};
 
 #[derive(Debug, PartialEq)]
-struct Stacks(Vec<Vec<char>>);
+pub struct Stacks(Vec<Vec<char>>);
 
 #[derive(Clone, Copy, PartialEq)]
-enum CraneModel {
+pub enum CraneModel {
     CrateMover9000,
     CrateMover9001
 }
@@ -13,11 +18,13 @@ impl FromStr for Stacks {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        // Assume `s` contains both stacks and instructions
-        let mut lines: Vec<&str> = s.lines().take_while(|line| !line.is_empty()).collect();
+        // Assume `s` contains both stacks and instructions. Crates sit in
+        // columns four characters wide, so a tab stands for one column.
+        let normalized = Normalizer::new().expand_tabs(4).normalize(s);
+        let mut lines: Vec<&str> = normalize::lines(&normalized.text).take_while(|line| !line.is_empty()).collect();
 
         let num_of_stacks = lines
-            .pop().unwrap().trim().chars().rev().next().unwrap() as usize - 48;
+            .pop().unwrap().trim().chars().next_back().unwrap() as usize - 48;
         lines.reverse();
 
         let mut stacks = vec![vec![]; num_of_stacks];
@@ -36,12 +43,25 @@ impl FromStr for Stacks {
 }
 
 impl Stacks {
-    fn arrange(&mut self, instructions: &[Instruction], crane_model: CraneModel) {
+    pub fn arrange(&mut self, instructions: &[Instruction], crane_model: CraneModel) {
         for i in instructions {
             self.move_crates(i, crane_model);
         }
     }
 
+    pub fn arrange_cancellable(
+        &mut self,
+        instructions: &[Instruction],
+        crane_model: CraneModel,
+        cancel: &CancellationToken,
+    ) -> Result<(), Cancelled> {
+        for i in instructions {
+            cancel.check()?;
+            self.move_crates(i, crane_model);
+        }
+        Ok(())
+    }
+
     fn move_crates(&mut self, Instruction { amount, from, to }: &Instruction, crane_model: CraneModel) {
         use CraneModel::*;
         let mut to_move = Vec::with_capacity(*amount);
@@ -55,7 +75,7 @@ impl Stacks {
         self.0[*to].append(&mut to_move);
     }
 
-    fn tops(&self) -> String {
+    pub fn tops(&self) -> String {
         self.0
             .iter()
             // Assume at least one char in stack
@@ -66,7 +86,7 @@ impl Stacks {
 
 
 #[derive(Debug, PartialEq)]
-struct Instruction {
+pub struct Instruction {
     amount: usize,
     from: usize,
     to: usize,
@@ -92,9 +112,8 @@ impl FromStr for Instruction {
     }
 }
 
-fn parse_instructions(file: &str) -> impl Iterator<Item=Instruction> + '_ {
-    file
-        .lines()
+pub fn parse_instructions(file: &str) -> impl Iterator<Item=Instruction> + '_ {
+    normalize::lines(file)
         .skip_while(|line| !line.is_empty())
         .skip(1)
         .filter_map(|line| line.parse().ok())
@@ -103,6 +122,7 @@ fn parse_instructions(file: &str) -> impl Iterator<Item=Instruction> + '_ {
 #[cfg(test)]
 mod tests {
     use std::str::FromStr;
+    use crate::cancel::{CancellationToken, Cancelled};
     use crate::day5::{Instruction, parse_instructions, Stacks};
     use crate::day5::CraneModel::*;
 
@@ -159,4 +179,48 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_cancelled_arrange() {
+        let input = include_str!("example.txt");
+        let mut stacks = Stacks::from_str(input).unwrap();
+        let instructions: Vec<Instruction> = parse_instructions(input).collect();
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+
+        assert_eq!(
+            stacks.arrange_cancellable(instructions.as_slice(), CrateMover9000, &cancel),
+            Err(Cancelled)
+        );
+        assert_eq!(
+            &stacks.tops(),
+            "NDP"
+        );
+    }
+
+    #[test]
+    fn test_example_with_lone_cr_and_tabs() {
+        let input = include_str!("example.txt").replace("    [D]", "\t[D]").replace('\n', "\r");
+        let mut stacks = Stacks::from_str(&input).unwrap();
+        let instructions: Vec<Instruction> = parse_instructions(&input).collect();
+
+        stacks.arrange(instructions.as_slice(), CrateMover9000);
+        assert_eq!(
+            &stacks.tops(),
+            "CMZ"
+        );
+    }
+
+    #[test]
+    fn test_example_with_bom_and_crlf() {
+        let input = format!("\u{feff}{}", include_str!("example.txt").replace('\n', " \r\n"));
+        let mut stacks = Stacks::from_str(&input).unwrap();
+        let instructions: Vec<Instruction> = parse_instructions(&input).collect();
+
+        stacks.arrange(instructions.as_slice(), CrateMover9000);
+        assert_eq!(
+            &stacks.tops(),
+            "CMZ"
+        );
+    }
+
 }
\ No newline at end of file
@@ -1,12 +1,18 @@
-use std::collections::HashSet;
+use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
+use crate::normalize;
 
-fn start_of_packet_index(s: &str) -> usize {
-    let chars: Vec<char> = s.chars().collect();
+fn signal(s: &str) -> Vec<char> {
+    normalize::lines(s).next().unwrap_or_default().chars().collect()
+}
+
+pub fn start_of_packet_index(s: &str) -> usize {
+    let chars = signal(s);
     first_window_with_all_different_characters(chars.as_slice(), 4)
 }
 
-fn start_of_message_index(s: &str) -> usize {
-    let chars: Vec<char> = s.chars().collect();
+pub fn start_of_message_index(s: &str) -> usize {
+    let chars = signal(s);
     first_window_with_all_different_characters(chars.as_slice(), 14)
 }
 
@@ -15,7 +21,7 @@ fn first_window_with_all_different_characters(signal: &[char], window_size: usiz
         .windows(window_size)
         .enumerate()
         .find_map(|(idx, window_of_chars)| {
-            if window_of_chars.iter().cloned().collect::<HashSet<char>>().len() == window_size {
+            if window_of_chars.iter().cloned().collect::<BTreeSet<char>>().len() == window_size {
                 Some(idx + window_size - 1)
             } else {
                 None
@@ -1,9 +1,13 @@
-use std::str::FromStr;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::str::FromStr;
+use crate::normalize;
 
 type Tokens = Vec<Vec<String>>;
 
 #[derive(Debug, PartialEq)]
-enum DirEntry {
+pub enum DirEntry {
     File(usize, String),
     Directory(Vec<DirEntry>, String)
 }
@@ -12,7 +16,7 @@ impl FromStr for DirEntry {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut cds = s
+        let mut cds = normalize::normalize(s)
             .split("$ cd ")
             .map(|dir_str| {
                 let mut dir_lines = dir_str
@@ -121,7 +125,7 @@ fn parse_directory(mut cds: Tokens) -> Result<(DirEntry, Tokens), String> {
 }
 
 // Star 1
-fn sum_of_sizes_smaller_than(dir: &DirEntry, limit: usize) -> usize {
+pub fn sum_of_sizes_smaller_than(dir: &DirEntry, limit: usize) -> usize {
     dir
         .directory_sizes()
         .iter()
@@ -130,7 +134,7 @@ fn sum_of_sizes_smaller_than(dir: &DirEntry, limit: usize) -> usize {
 }
 
 // Star 2
-fn smallest_size_to_delete(dir: &DirEntry) -> usize {
+pub fn smallest_size_to_delete(dir: &DirEntry) -> usize {
     let mut sizes = dir
         .directory_sizes();
     sizes.sort();
@@ -195,4 +199,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_example_with_crlf() {
+        let input = include_str!("example.txt").replace('\n', "\r\n");
+        let dir = input.parse::<DirEntry>().unwrap();
+
+        assert_eq!(
+            sum_of_sizes_smaller_than(&dir, 100000),
+            95437
+        );
+    }
+
+    #[test]
+    fn test_example_with_lone_cr() {
+        let input = include_str!("example.txt").replace('\n', "\r");
+        let dir = input.parse::<DirEntry>().unwrap();
+
+        assert_eq!(
+            sum_of_sizes_smaller_than(&dir, 100000),
+            95437
+        );
+    }
+
 }
\ No newline at end of file
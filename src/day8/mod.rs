@@ -1,14 +1,17 @@
-use std::str::FromStr;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::str::FromStr;
+use crate::normalize;
 
-struct Forest(Vec<Vec<u32>>);
+pub struct Forest(Vec<Vec<u32>>);
 
 impl FromStr for Forest {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Ok(Self(
-            s
-                .lines()
+            normalize::lines(s)
                 .map(|line| line.chars().map(|c| c.to_digit(10).unwrap()).collect())
                 .collect()
         ))
@@ -29,16 +32,17 @@ impl Forest {
         Self(flipped)
     }
 
+    #[allow(clippy::needless_range_loop)]
     fn transposed(&self) -> Self {
         // Assume at least 1
         let rows = self.0.len();
         let cols = self.0[0].len();
 
-        let mut t = vec![vec![self.0[0][0].clone(); rows]; cols];
+        let mut t = vec![vec![self.0[0][0]; rows]; cols];
 
         for r in 0..rows {
             for c in 0..cols {
-                t[c][r] = self.0[r][c].clone();
+                t[c][r] = self.0[r][c];
             }
         }
 
@@ -88,6 +92,7 @@ impl Forest {
             .transposed()
     }
 
+    #[allow(clippy::needless_range_loop)]
     fn visible_trees(&self) -> Vec<Vec<bool>> {
         let lmax = self.left_max();
         let rmax = self.right_max();
@@ -115,7 +120,7 @@ impl Forest {
         t
     }
 
-    fn number_of_visible_trees(&self) -> usize {
+    pub fn number_of_visible_trees(&self) -> usize {
         self
             .visible_trees()
             .iter()
@@ -123,6 +128,7 @@ impl Forest {
             .sum()
     }
 
+    #[allow(clippy::needless_range_loop)]
     fn scenic_scores(&self) -> Vec<Vec<u32>> {
         let rows = self.0.len();
         let cols = self.0[0].len();
@@ -160,7 +166,7 @@ impl Forest {
         }
     }
 
-    fn max_scenic_score(&self) -> u32 {
+    pub fn max_scenic_score(&self) -> u32 {
         *self
             .scenic_scores()
             .iter()
@@ -1,6 +1,10 @@
-use std::collections::HashSet;
-use std::ops::{Add, Sub};
-use std::str::FromStr;
+use alloc::collections::BTreeSet;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use core::ops::{Add, Sub};
+use core::str::FromStr;
+use crate::cancel::{CancellationToken, Cancelled};
+use crate::normalize;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum Direction {
@@ -11,7 +15,7 @@ enum Direction {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
-struct Instruction {
+pub struct Instruction {
     direction: Direction,
     amount: usize
 }
@@ -36,8 +40,8 @@ impl FromStr for Instruction {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Vec2 { x: i32, y: i32 }
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Vec2 { x: i32, y: i32 }
 impl Add for Vec2 {
     type Output = Self;
     fn add(self, rhs: Self) -> Self::Output { Self { x: self.x + rhs.x, y: self.y + rhs.y } }
@@ -77,27 +81,45 @@ impl Vec2 {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
-struct Rope<const N: usize> {
+pub struct Rope<const N: usize> {
     knots: [Vec2; N]
 }
 
+impl<const N: usize> Default for Rope<N> {
+    fn default() -> Self { Self::new() }
+}
+
 impl<const N: usize> Rope<N> {
-    fn new() -> Self { Self { knots: [Vec2::new(); N] } }
+    pub fn new() -> Self { Self { knots: [Vec2::new(); N] } }
     fn head(&self) -> Vec2 { self.knots[0] }
     fn last(&self) -> Vec2 { self.knots[N-1] }
 
-    fn move_by_instrcutions(&self, instructions: impl Iterator<Item=Instruction>) -> (Self, HashSet<Vec2>) {
+    pub fn move_by_instrcutions(&self, instructions: impl Iterator<Item=Instruction>) -> (Self, BTreeSet<Vec2>) {
         instructions
-            .fold((*self, HashSet::from([self.last()])), |(rope, mut coords), instruction| {
+            .fold((*self, BTreeSet::from([self.last()])), |(rope, mut coords), instruction| {
                 let (rope, new_coords) = rope.move_by_instruction(instruction);
                 coords.extend(new_coords);
                 (rope, coords)
             })
     }
 
-    fn move_by_instruction(&self, instruction: Instruction) -> (Self, HashSet<Vec2>) {
+    pub fn move_by_instructions_cancellable(
+        &self,
+        mut instructions: impl Iterator<Item=Instruction>,
+        cancel: &CancellationToken,
+    ) -> Result<(Self, BTreeSet<Vec2>), Cancelled> {
+        instructions
+            .try_fold((*self, BTreeSet::from([self.last()])), |(rope, mut coords), instruction| {
+                cancel.check()?;
+                let (rope, new_coords) = rope.move_by_instruction(instruction);
+                coords.extend(new_coords);
+                Ok((rope, coords))
+            })
+    }
+
+    fn move_by_instruction(&self, instruction: Instruction) -> (Self, BTreeSet<Vec2>) {
         (0..instruction.amount)
-            .fold((*self, HashSet::from([self.last()])), |(rope, mut coords), _| {
+            .fold((*self, BTreeSet::from([self.last()])), |(rope, mut coords), _| {
                 let rope = rope.move_knots(instruction.direction);
                 coords.insert(rope.last());
                 (rope, coords)
@@ -126,8 +148,8 @@ impl<const N: usize> Rope<N> {
     }
 }
 
-fn parse_instructions(s: &str) -> impl Iterator<Item=Instruction> + '_ {
-    s.lines()
+pub fn parse_instructions(s: &str) -> impl Iterator<Item=Instruction> + '_ {
+    normalize::lines(s)
         .filter_map(|line| line.parse::<Instruction>().ok())
 }
 
@@ -1,13 +1,23 @@
-#![feature(test)]
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
+#![cfg_attr(test, feature(test))]
 
+extern crate alloc;
+#[cfg(test)]
 extern crate test;
 
-mod day1;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
\ No newline at end of file
+pub mod cancel;
+pub mod day1;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+pub mod normalize;
+pub mod rng;
+#[cfg(feature = "std")]
+pub mod runner;
+#[cfg(feature = "std")]
+pub mod stress;
@@ -0,0 +1,203 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+use std::sync::Arc;
+use std::time::Duration;
+
+use aoc2022::day1;
+use aoc2022::day1::query::Query;
+use aoc2022::day1::stats::CalorieStats;
+use aoc2022::day2;
+use aoc2022::day2::infer::Reading;
+use aoc2022::day2::stats::GuideStats;
+use aoc2022::normalize::Normalizer;
+use aoc2022::runner::{self, Runner, SOLUTIONS};
+use aoc2022::stress;
+
+const USAGE: &str = "\
+usage: aoc2022 [run] [DAY...] [--budget-ms MILLIS] [--show-changes]
+       aoc2022 [run] DAY --input PATH [--budget-ms MILLIS] [--show-changes]
+       aoc2022 stress [DAY...] [--seed SEED] [--scale FACTOR] [--budget-ms MILLIS]
+       aoc2022 calorie-stats [--input PATH] [--buckets N]
+       aoc2022 guide-stats [--input PATH]
+       aoc2022 query (more-than CALORIES | snack-heavier-than CALORIES | by-snack-count | fewest-exceeding CALORIES) [--input PATH]";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        Some("run") => run(&args[1..]),
+        Some("stress") => stress(&args[1..]),
+        Some("calorie-stats") => calorie_stats(&args[1..]),
+        Some("guide-stats") => guide_stats(&args[1..]),
+        Some("query") => query(&args[1..]),
+        _ => run(&args),
+    };
+
+    if let Err(message) = result {
+        eprintln!("{}\n{}", message, USAGE);
+        process::exit(2);
+    }
+}
+
+/// Options shared by the subcommands
+struct Options {
+    runner: Runner,
+    days: Vec<u32>,
+    seed: u64,
+    scale: f64,
+    input: Option<PathBuf>,
+    buckets: usize,
+    /// Print what normalization changed in each input
+    show_changes: bool,
+}
+
+/// Parses `args` for `command`, which takes only the given `flags` and, if
+/// `takes_days`, the days to work on (all of them if none are given)
+fn parse_options(args: &[String], command: &str, flags: &[&str], takes_days: bool) -> Result<Options, String> {
+    let mut options = Options {
+        runner: Runner::new(),
+        days: vec![],
+        seed: 2022,
+        scale: 1.0,
+        input: None,
+        buckets: 10,
+        show_changes: false,
+    };
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg.starts_with("--") && !flags.contains(&arg.as_str()) {
+            return Err(format!("{} does not take {}", command, arg));
+        }
+        match arg.as_str() {
+            "--budget-ms" => {
+                let millis: u64 = parse_value(arg, args.next())?;
+                options.runner = options.runner.with_budget(Duration::from_millis(millis));
+            }
+            "--seed" => options.seed = parse_value(arg, args.next())?,
+            "--scale" => options.scale = parse_value(arg, args.next())?,
+            "--input" => options.input = Some(parse_value(arg, args.next())?),
+            "--buckets" => options.buckets = parse_value(arg, args.next())?,
+            "--show-changes" => options.show_changes = true,
+            day if takes_days => options.days.push(day.parse::<u32>().map_err(|_| format!("invalid day: {}", day))?),
+            other => return Err(format!("{} does not take days, got {}", command, other)),
+        }
+    }
+
+    if takes_days && options.days.is_empty() {
+        options.days = SOLUTIONS.iter().map(|solution| solution.day).collect();
+        options.days.dedup();
+    }
+
+    Ok(options)
+}
+
+fn parse_value<T: std::str::FromStr>(flag: &str, value: Option<&String>) -> Result<T, String> {
+    value
+        .ok_or_else(|| format!("{} needs a value", flag))?
+        .parse()
+        .map_err(|_| format!("invalid value for {}", flag))
+}
+
+/// The file given with `--input`, or the bundled input of `day`
+fn read_input(day: u32, options: &Options) -> Result<String, String> {
+    let (path, read) = match &options.input {
+        Some(path) => (path.clone(), fs::read_to_string(path)),
+        None => (runner::input_path(day), runner::load_input(day)),
+    };
+    read.map_err(|e| format!("cannot read {}: {}", path.display(), e))
+}
+
+fn run(args: &[String]) -> Result<(), String> {
+    let options = parse_options(args, "run", &["--budget-ms", "--input", "--show-changes"], true)?;
+    if options.input.is_some() && options.days.len() != 1 {
+        return Err("--input needs exactly one day to run".to_string());
+    }
+
+    for &day in &options.days {
+        let input: Arc<str> = read_input(day, &options)?.into();
+        if options.show_changes {
+            println!("day {} input: {}", day, Normalizer::new().normalize(&input).changes);
+        }
+        for solution in runner::solutions_for_day(day) {
+            println!("{}", options.runner.run(solution, input.clone()));
+        }
+    }
+
+    Ok(())
+}
+
+fn stress(args: &[String]) -> Result<(), String> {
+    let options = parse_options(args, "stress", &["--seed", "--scale", "--budget-ms"], true)?;
+
+    for day in options.days {
+        let size = (stress::default_size(day) as f64 * options.scale).round() as usize;
+        println!("day {}: size {}, seed {}", day, size, options.seed);
+        for throughput in stress::run(&options.runner, day, size, options.seed) {
+            println!("  {}", throughput);
+        }
+    }
+
+    Ok(())
+}
+
+fn calorie_stats(args: &[String]) -> Result<(), String> {
+    let options = parse_options(args, "calorie-stats", &["--input", "--buckets"], false)?;
+    let elves = day1::parse_calories_as::<u64>(&read_input(1, &options)?).map_err(|e| e.to_string())?;
+    let stats = CalorieStats::new(&elves).map_err(|e| e.to_string())?;
+
+    print!("{}", stats);
+    print!("{}", stats.histogram(options.buckets, 40));
+
+    Ok(())
+}
+
+fn guide_stats(args: &[String]) -> Result<(), String> {
+    let options = parse_options(args, "guide-stats", &["--input"], false)?;
+    let guide = read_input(2, &options)?;
+    let rules = day2::Rules::default();
+
+    println!("read as shapes:");
+    print!("{}", GuideStats::of_guide(&guide, &rules, Reading::Shapes)?);
+    println!("\nread as outcomes:");
+    print!("{}", GuideStats::of_guide(&guide, &rules, Reading::Outcomes)?);
+
+    Ok(())
+}
+
+fn query(args: &[String]) -> Result<(), String> {
+    let question = args.first().ok_or("query needs a question")?;
+    let (calories, rest) = match question.as_str() {
+        "by-snack-count" => (0, &args[1..]),
+        "more-than" | "snack-heavier-than" | "fewest-exceeding" =>
+            (parse_value(question, args.get(1))?, args.get(2..).unwrap_or_default()),
+        other => return Err(format!("unknown query: {}", other)),
+    };
+    let options = parse_options(rest, "query", &["--input"], false)?;
+    let elves = day1::parse_calories_as::<u64>(&read_input(1, &options)?).map_err(|e| e.to_string())?;
+    let query = Query::new(&elves);
+
+    match question.as_str() {
+        "more-than" => {
+            let found = query.carrying_more_than(calories).map_err(|e| e.to_string())?;
+            println!("{} elves carry more than {} calories: {:?}", found.len(), calories, found);
+        }
+        "snack-heavier-than" => {
+            let found = query.with_snack_heavier_than(calories);
+            println!("{} elves carry a snack of more than {} calories: {:?}", found.len(), calories, found);
+        }
+        "by-snack-count" => {
+            for (count, found) in query.grouped_by_snack_count() {
+                println!("{:>3} snacks: {} elves {:?}", count, found.len(), found);
+            }
+        }
+        "fewest-exceeding" => match query.fewest_exceeding(calories).map_err(|e| e.to_string())? {
+            Some(found) => println!("{} elves together carry more than {} calories: {:?}", found.len(), calories, found),
+            None => println!("all elves together carry at most {} calories", calories),
+        },
+        _ => unreachable!("checked above"),
+    }
+
+    Ok(())
+}
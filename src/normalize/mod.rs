@@ -0,0 +1,242 @@
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use core::fmt;
+
+const BOM: char = '\u{feff}';
+
+/// What `Normalizer::normalize` had to change to get clean input.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Changes {
+    pub bom_stripped: bool,
+    pub line_endings_normalized: usize,
+    pub lines_trimmed: usize,
+    pub tabs_expanded: usize,
+}
+
+impl Changes {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl fmt::Display for Changes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "no changes");
+        }
+
+        let mut parts = vec![];
+        if self.bom_stripped {
+            parts.push("stripped BOM".to_string());
+        }
+        if self.line_endings_normalized > 0 {
+            parts.push(format!("normalized {} line endings", self.line_endings_normalized));
+        }
+        if self.lines_trimmed > 0 {
+            parts.push(format!("trimmed {} lines", self.lines_trimmed));
+        }
+        if self.tabs_expanded > 0 {
+            parts.push(format!("expanded {} tabs", self.tabs_expanded));
+        }
+
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Normalized<'a> {
+    pub text: Cow<'a, str>,
+    pub changes: Changes,
+}
+
+/// Cleans up puzzle input before it reaches a parser: strips a leading BOM,
+/// turns `\r\n` and lone `\r` into `\n`, trims trailing whitespace from every
+/// line and, if asked to, expands tabs to the given tab width.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Normalizer {
+    tab_width: Option<usize>,
+}
+
+impl Normalizer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn expand_tabs(self, tab_width: usize) -> Self {
+        Self { tab_width: Some(tab_width.max(1)) }
+    }
+
+    pub fn normalize<'a>(&self, s: &'a str) -> Normalized<'a> {
+        let mut changes = Changes::default();
+
+        let body = match s.strip_prefix(BOM) {
+            Some(rest) => {
+                changes.bom_stripped = true;
+                rest
+            }
+            None => s,
+        };
+
+        let mut text = String::with_capacity(body.len());
+        let mut rest = body;
+
+        while !rest.is_empty() {
+            let (line, ending, next) = match rest.find(['\r', '\n']) {
+                None => (rest, "", ""),
+                Some(idx) => {
+                    let (line, tail) = rest.split_at(idx);
+                    match tail.strip_prefix("\r\n") {
+                        Some(next) => (line, "\r\n", next),
+                        None => (line, &tail[..1], &tail[1..]),
+                    }
+                }
+            };
+
+            if !ending.is_empty() && ending != "\n" {
+                changes.line_endings_normalized += 1;
+            }
+
+            let trimmed = line.trim_end();
+            if trimmed.len() != line.len() {
+                changes.lines_trimmed += 1;
+            }
+
+            match self.tab_width {
+                Some(tab_width) => changes.tabs_expanded += expand_tabs_into(&mut text, trimmed, tab_width),
+                None => text.push_str(trimmed),
+            }
+
+            if !ending.is_empty() {
+                text.push('\n');
+            }
+            rest = next;
+        }
+
+        let text = if changes.is_empty() { Cow::Borrowed(s) } else { Cow::Owned(text) };
+
+        Normalized { text, changes }
+    }
+}
+
+/// Push `line` to `out` with tabs replaced by spaces up to the next tab stop,
+/// returning the number of tabs replaced.
+fn expand_tabs_into(out: &mut String, line: &str, tab_width: usize) -> usize {
+    let mut column = 0;
+    let mut expanded = 0;
+
+    for c in line.chars() {
+        if c == '\t' {
+            let spaces = tab_width - column % tab_width;
+            out.extend(core::iter::repeat_n(' ', spaces));
+            column += spaces;
+            expanded += 1;
+        } else {
+            out.push(c);
+            column += 1;
+        }
+    }
+
+    expanded
+}
+
+/// Normalize with the default settings, i.e. without tab expansion.
+pub fn normalize(s: &str) -> Cow<'_, str> {
+    Normalizer::new().normalize(s).text
+}
+
+/// Lines of `s` as a line-oriented parser wants them: without a leading BOM,
+/// split on `\n`, `\r\n` and lone `\r` like `Normalizer` does, and without
+/// trailing whitespace. A last line without a line ending that is only
+/// whitespace is dropped, as it is empty once normalized. Borrows from `s`,
+/// so it can stand in for `str::lines` in parsers returning iterators.
+pub fn lines(s: &str) -> impl Iterator<Item=&str> + '_ {
+    let mut rest = s.strip_prefix(BOM).unwrap_or(s);
+    core::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+        let line = match rest.find(['\r', '\n']) {
+            None => match core::mem::take(&mut rest).trim_end() {
+                "" => return None,
+                line => line,
+            },
+            Some(idx) => {
+                let (line, tail) = rest.split_at(idx);
+                rest = tail.strip_prefix("\r\n").unwrap_or(&tail[1..]);
+                line
+            }
+        };
+        Some(line.trim_end())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+    use crate::normalize::{Changes, lines, normalize, Normalizer};
+
+    #[test]
+    fn test_clean_input_is_borrowed() {
+        let input = include_str!("../day1/example.txt");
+        let normalized = Normalizer::new().normalize(input);
+
+        assert!(matches!(normalized.text, Cow::Borrowed(_)));
+        assert!(normalized.changes.is_empty());
+    }
+
+    #[test]
+    fn test_bom_line_endings_and_trailing_whitespace() {
+        let normalized = Normalizer::new().normalize("\u{feff}1000  \r\n2000\r3000\t\n\n4000");
+
+        assert_eq!(normalized.text, "1000\n2000\n3000\n\n4000");
+        assert_eq!(
+            normalized.changes,
+            Changes {
+                bom_stripped: true,
+                line_endings_normalized: 2,
+                lines_trimmed: 2,
+                tabs_expanded: 0,
+            }
+        );
+        assert_eq!(
+            normalized.changes.to_string(),
+            "stripped BOM, normalized 2 line endings, trimmed 2 lines"
+        );
+    }
+
+    #[test]
+    fn test_expand_tabs() {
+        let normalized = Normalizer::new()
+            .expand_tabs(4)
+            .normalize("\t[D]\n[N]\t[C]\t\n");
+
+        assert_eq!(normalized.text, "    [D]\n[N] [C]\n");
+        assert_eq!(normalized.changes.tabs_expanded, 2);
+        assert_eq!(normalized.changes.lines_trimmed, 1);
+    }
+
+    #[test]
+    fn test_lines() {
+        assert_eq!(
+            lines("\u{feff}A Y \r\nB X\r\n\r\nC Z").collect::<Vec<&str>>(),
+            vec!["A Y", "B X", "", "C Z"]
+        );
+        assert_eq!(normalize("abc\r\n"), "abc\n");
+    }
+
+    #[test]
+    fn test_lines_match_normalizer() {
+        for input in ["1000\r2000\r\r3000", "a\r\n\rb\n", "\u{feff}x \n\n", "\r", "", "x\n \t", " ", "a\r\t"] {
+            assert_eq!(
+                lines(input).collect::<Vec<&str>>(),
+                normalize(input).lines().collect::<Vec<&str>>()
+            );
+        }
+        assert_eq!(
+            lines("1000\r2000\r\r3000").collect::<Vec<&str>>(),
+            vec!["1000", "2000", "", "3000"]
+        );
+    }
+}
@@ -0,0 +1,301 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::cancel::{CancellationToken, Cancelled};
+use crate::day2::fast::ScoreTable;
+use crate::day2::infer::Reading;
+use crate::day3::SharedPolicy;
+use crate::day3::alphabet::Alphabet;
+use crate::day5::CraneModel;
+use crate::day9::Rope;
+use crate::{day1, day2, day3, day4, day5, day6, day7, day8, day9};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SolveError {
+    Cancelled,
+    Failed(String),
+}
+
+impl From<Cancelled> for SolveError {
+    fn from(_: Cancelled) -> Self {
+        SolveError::Cancelled
+    }
+}
+
+impl From<String> for SolveError {
+    fn from(message: String) -> Self {
+        SolveError::Failed(message)
+    }
+}
+
+/// For solver errors that are not `String`s
+pub fn failed(error: impl fmt::Display) -> SolveError {
+    SolveError::Failed(error.to_string())
+}
+
+pub type Solver = fn(&str, &CancellationToken) -> Result<String, SolveError>;
+
+#[derive(Clone, Copy)]
+pub struct Solution {
+    pub day: u32,
+    pub part: u32,
+    pub solve: Solver,
+}
+
+pub const SOLUTIONS: &[Solution] = &[
+    Solution { day: 1, part: 1, solve: |input, _| {
+        let elves = day1::parse_calories_as::<u64>(input).map_err(failed)?;
+        let most = day1::most_calories(&elves).map_err(failed)?;
+        Ok(most.ok_or_else(|| failed("no elves in input"))?.to_string())
+    }},
+    Solution { day: 1, part: 2, solve: |input, _| {
+        let elves = day1::parse_calories_as::<u64>(input).map_err(failed)?;
+        Ok(day1::top_three(&elves).map_err(failed)?.to_string())
+    }},
+    Solution { day: 2, part: 1, solve: |input, _| {
+        let table = ScoreTable::new(&day2::Rules::default(), Reading::Shapes)?;
+        Ok(table.score_parallel(input.as_bytes(), threads()).to_string())
+    }},
+    Solution { day: 2, part: 2, solve: |input, _| {
+        let table = ScoreTable::new(&day2::Rules::default(), Reading::Outcomes)?;
+        Ok(table.score_parallel(input.as_bytes(), threads()).to_string())
+    }},
+    Solution { day: 3, part: 1, solve: |input, _| {
+        Ok(day3::sum_priorities_with(input, &Alphabet::default(), SharedPolicy::Error).map_err(failed)?.to_string())
+    }},
+    Solution { day: 3, part: 2, solve: |input, _| {
+        Ok(day3::sum_group_priorities_with(input, &Alphabet::default(), 3, SharedPolicy::Error).map_err(failed)?.to_string())
+    }},
+    Solution { day: 4, part: 1, solve: |input, _| {
+        Ok(day4::pairs_with_fully_contained_assignments(day4::parse_elfs(input)).count().to_string())
+    }},
+    Solution { day: 4, part: 2, solve: |input, _| {
+        Ok(day4::pairs_with_overlap(day4::parse_elfs(input)).count().to_string())
+    }},
+    Solution { day: 5, part: 1, solve: |input, cancel| {
+        solve_day5(input, CraneModel::CrateMover9000, cancel)
+    }},
+    Solution { day: 5, part: 2, solve: |input, cancel| {
+        solve_day5(input, CraneModel::CrateMover9001, cancel)
+    }},
+    Solution { day: 6, part: 1, solve: |input, _| {
+        Ok((day6::start_of_packet_index(input) + 1).to_string())
+    }},
+    Solution { day: 6, part: 2, solve: |input, _| {
+        Ok((day6::start_of_message_index(input) + 1).to_string())
+    }},
+    Solution { day: 7, part: 1, solve: |input, _| {
+        Ok(day7::sum_of_sizes_smaller_than(&input.parse()?, 100000).to_string())
+    }},
+    Solution { day: 7, part: 2, solve: |input, _| {
+        Ok(day7::smallest_size_to_delete(&input.parse()?).to_string())
+    }},
+    Solution { day: 8, part: 1, solve: |input, _| {
+        Ok(input.parse::<day8::Forest>()?.number_of_visible_trees().to_string())
+    }},
+    Solution { day: 8, part: 2, solve: |input, _| {
+        Ok(input.parse::<day8::Forest>()?.max_scenic_score().to_string())
+    }},
+    Solution { day: 9, part: 1, solve: |input, cancel| {
+        let (_, coords) = Rope::<2>::new()
+            .move_by_instructions_cancellable(day9::parse_instructions(input), cancel)?;
+        Ok(coords.len().to_string())
+    }},
+    Solution { day: 9, part: 2, solve: |input, cancel| {
+        let (_, coords) = Rope::<10>::new()
+            .move_by_instructions_cancellable(day9::parse_instructions(input), cancel)?;
+        Ok(coords.len().to_string())
+    }},
+];
+
+fn solve_day5(input: &str, crane_model: CraneModel, cancel: &CancellationToken) -> Result<String, SolveError> {
+    let mut stacks = day5::Stacks::from_str(input)?;
+    let instructions: Vec<day5::Instruction> = day5::parse_instructions(input).collect();
+    stacks.arrange_cancellable(instructions.as_slice(), crane_model, cancel)?;
+    Ok(stacks.tops())
+}
+
+/// Threads for solvers that split their input
+fn threads() -> usize {
+    thread::available_parallelism().map_or(1, |threads| threads.get())
+}
+
+pub fn solutions_for_day(day: u32) -> impl Iterator<Item=&'static Solution> {
+    SOLUTIONS
+        .iter()
+        .filter(move |solution| solution.day == day)
+}
+
+/// Path of the bundled puzzle input of `day`.
+pub fn input_path(day: u32) -> PathBuf {
+    [env!("CARGO_MANIFEST_DIR"), "src", &format!("day{}", day), "input.txt"]
+        .iter()
+        .collect()
+}
+
+pub fn load_input(day: u32) -> io::Result<String> {
+    fs::read_to_string(input_path(day))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Status {
+    Solved(String),
+    Failed(String),
+    Cancelled,
+    Timeout,
+    Panicked,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    pub day: u32,
+    pub part: u32,
+    pub status: Status,
+    pub elapsed: Duration,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "day {} part {}: ", self.day, self.part)?;
+        match &self.status {
+            Status::Solved(answer) => write!(f, "{} ({:?})", answer, self.elapsed),
+            Status::Failed(message) => write!(f, "FAILED: {}", message),
+            Status::Cancelled => write!(f, "CANCELLED after {:?}", self.elapsed),
+            Status::Timeout => write!(f, "TIMEOUT after {:?}", self.elapsed),
+            Status::Panicked => write!(f, "PANICKED"),
+        }
+    }
+}
+
+/// Recursive parsers such as day 7 need more than the default stack on deeply
+/// nested inputs.
+const WORKER_STACK_SIZE: usize = 256 * 1024 * 1024;
+
+/// Runs solutions on a worker thread each, giving up on a part once its time
+/// budget is spent. A part that overruns is told to stop through its
+/// `CancellationToken`; solvers that never check the token keep running in
+/// the background until the process exits, but the runner does not wait for
+/// them.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Runner {
+    budget: Option<Duration>,
+}
+
+impl Runner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_budget(self, budget: Duration) -> Self {
+        Self { budget: Some(budget) }
+    }
+
+    pub fn run(&self, solution: &Solution, input: Arc<str>) -> Report {
+        let cancel = CancellationToken::new();
+        let (sender, receiver) = mpsc::channel();
+        let solve = solution.solve;
+        let worker_cancel = cancel.clone();
+
+        let start = Instant::now();
+        let spawned = thread::Builder::new()
+            .stack_size(WORKER_STACK_SIZE)
+            .spawn(move || {
+                // The receiver is gone if the part timed out, nothing to report then
+                let _ = sender.send(solve(&input, &worker_cancel));
+            });
+        if let Err(e) = spawned {
+            return Report {
+                day: solution.day,
+                part: solution.part,
+                status: Status::Failed(format!("cannot spawn worker: {}", e)),
+                elapsed: start.elapsed(),
+            };
+        }
+
+        let received = match self.budget {
+            Some(budget) => receiver.recv_timeout(budget),
+            None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+
+        let status = match received {
+            Ok(Ok(answer)) => Status::Solved(answer),
+            Ok(Err(SolveError::Failed(message))) => Status::Failed(message),
+            Ok(Err(SolveError::Cancelled)) => Status::Cancelled,
+            Err(RecvTimeoutError::Timeout) => {
+                cancel.cancel();
+                Status::Timeout
+            }
+            Err(RecvTimeoutError::Disconnected) => Status::Panicked,
+        };
+
+        Report {
+            day: solution.day,
+            part: solution.part,
+            status,
+            elapsed: start.elapsed(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::thread;
+    use std::time::{Duration, Instant};
+    use crate::runner::{Runner, Solution, solutions_for_day, SolveError, Status};
+
+    #[test]
+    fn test_run_example() {
+        let input: Arc<str> = include_str!("../day5/example.txt").into();
+        let tops: Vec<Status> = solutions_for_day(5)
+            .map(|solution| Runner::new().run(solution, input.clone()).status)
+            .collect();
+
+        assert_eq!(
+            tops,
+            vec![Status::Solved("CMZ".to_string()), Status::Solved("MCD".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_timeout_cancels_solver() {
+        static STOPPED: AtomicBool = AtomicBool::new(false);
+        let runaway = Solution { day: 0, part: 1, solve: |_, cancel| {
+            while cancel.check().is_ok() {
+                thread::sleep(Duration::from_millis(1));
+            }
+            STOPPED.store(true, Ordering::SeqCst);
+            Err(SolveError::Cancelled)
+        }};
+
+        let report = Runner::new()
+            .with_budget(Duration::from_millis(20))
+            .run(&runaway, "".into());
+
+        assert_eq!(report.status, Status::Timeout);
+        assert!(report.to_string().starts_with("day 0 part 1: TIMEOUT"));
+
+        let deadline = Instant::now() + Duration::from_secs(1);
+        while !STOPPED.load(Ordering::SeqCst) && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(1));
+        }
+        assert!(STOPPED.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_failure_and_panic() {
+        let failing = Solution { day: 0, part: 1, solve: |_, _| Err("bad input".to_string().into()) };
+        let panicking = Solution { day: 0, part: 2, solve: |_, _| panic!("oh no") };
+
+        assert_eq!(Runner::new().run(&failing, "".into()).status, Status::Failed("bad input".to_string()));
+        assert_eq!(Runner::new().run(&panicking, "".into()).status, Status::Panicked);
+    }
+}
@@ -0,0 +1,283 @@
+use std::fmt;
+use std::fmt::Write;
+use std::sync::Arc;
+
+use crate::rng::Rng;
+use crate::runner::{self, Report, Runner, Status};
+
+const ITEMS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// Input size used for `day` when no scale is given, in the unit the
+/// generator of that day counts in (elves, rounds, rucksacks, pairs, moves,
+/// characters, nesting depth, forest side and motions respectively).
+pub fn default_size(day: u32) -> usize {
+    match day {
+        1 => 1_000_000,
+        2 => 10_000_000,
+        3 => 1_000_000,
+        4 => 1_000_000,
+        5 => 1_000_000,
+        6 => 10_000_000,
+        7 => 2_000,
+        8 => 5_000,
+        9 => 1_000_000,
+        _ => 0,
+    }
+}
+
+/// Generate a valid puzzle input for `day`. The same `size` and `seed` always
+/// give the same input.
+pub fn generate(day: u32, size: usize, seed: u64) -> Option<String> {
+    let mut rng = Rng::new(seed);
+    let size = size.max(1);
+    let input = match day {
+        1 => calories(size, &mut rng),
+        2 => rounds(size, &mut rng),
+        3 => rucksacks(size, &mut rng),
+        4 => assignments(size, &mut rng),
+        5 => crates(size, &mut rng),
+        6 => signal(size, &mut rng),
+        7 => transcript(size, &mut rng),
+        8 => forest(size, &mut rng),
+        9 => motions(size, &mut rng),
+        _ => return None,
+    };
+    Some(input)
+}
+
+fn calories(elves: usize, rng: &mut Rng) -> String {
+    let mut s = String::new();
+    for elf in 0..elves {
+        if elf > 0 {
+            s.push('\n');
+        }
+        for _ in 0..rng.between(1, 15) {
+            writeln!(s, "{}", rng.between(1000, 60000)).unwrap();
+        }
+    }
+    s
+}
+
+fn rounds(rounds: usize, rng: &mut Rng) -> String {
+    let mut s = String::with_capacity(rounds * 4);
+    for _ in 0..rounds {
+        s.push(*rng.choose(&['A', 'B', 'C']));
+        s.push(' ');
+        s.push(*rng.choose(&['X', 'Y', 'Z']));
+        s.push('\n');
+    }
+    s
+}
+
+/// Groups of three rucksacks sharing exactly one badge, each rucksack having
+/// exactly one item in both compartments.
+fn rucksacks(rucksacks: usize, rng: &mut Rng) -> String {
+    let mut s = String::new();
+    for _ in 0..rucksacks.div_ceil(3) {
+        let mut items = ITEMS.to_vec();
+        rng.shuffle(&mut items);
+        let badge = items[0];
+        for pool in items[1..].chunks(17) {
+            let shared = pool[0];
+            let (left_pool, right_pool) = pool[1..].split_at(8);
+            let half = rng.between(2, 16) as usize;
+            let mut left: Vec<u8> = (0..half).map(|_| *rng.choose(left_pool)).collect();
+            let mut right: Vec<u8> = (0..half).map(|_| *rng.choose(right_pool)).collect();
+            left[0] = shared;
+            right[0] = shared;
+            left[1] = badge;
+            rng.shuffle(&mut left);
+            rng.shuffle(&mut right);
+            s.extend(left.iter().chain(right.iter()).map(|&c| c as char));
+            s.push('\n');
+        }
+    }
+    s
+}
+
+fn assignments(pairs: usize, rng: &mut Rng) -> String {
+    let mut s = String::new();
+    for _ in 0..pairs {
+        let a = rng.between(1, 99);
+        let b = rng.between(a, 99);
+        let c = rng.between(1, 99);
+        let d = rng.between(c, 99);
+        writeln!(s, "{}-{},{}-{}", a, b, c, d).unwrap();
+    }
+    s
+}
+
+/// Nine stacks and moves that never empty a stack, so every stack has a top
+/// crate in the end.
+fn crates(moves: usize, rng: &mut Rng) -> String {
+    let mut heights: Vec<usize> = (0..9).map(|_| rng.between(2, 8) as usize).collect();
+    let tallest = *heights.iter().max().unwrap();
+
+    let mut s = String::new();
+    for level in (0..tallest).rev() {
+        let row: Vec<String> = heights
+            .iter()
+            .map(|&height| if level < height {
+                format!("[{}]", (b'A' + rng.below(26) as u8) as char)
+            } else {
+                "   ".to_string()
+            })
+            .collect();
+        writeln!(s, "{}", row.join(" ").trim_end()).unwrap();
+    }
+    writeln!(s, " {}", (1..=9).map(|n| n.to_string()).collect::<Vec<String>>().join("   ")).unwrap();
+    s.push('\n');
+
+    for _ in 0..moves {
+        let from = loop {
+            let from = rng.below(9) as usize;
+            if heights[from] > 1 {
+                break from;
+            }
+        };
+        let to = (from + rng.between(1, 8) as usize) % 9;
+        let amount = rng.between(1, heights[from] as u64 - 1) as usize;
+        heights[from] -= amount;
+        heights[to] += amount;
+        writeln!(s, "move {} from {} to {}", amount, from + 1, to + 1).unwrap();
+    }
+    s
+}
+
+/// Noise from a three letter alphabet ending in "aa", followed by fourteen
+/// distinct letters starting with another 'a', so both markers are found only
+/// at the very end.
+fn signal(length: usize, rng: &mut Rng) -> String {
+    let mut s: String = (0..length.saturating_sub(2))
+        .map(|_| *rng.choose(&['a', 'b', 'c']))
+        .collect();
+    s.push_str("aa");
+    s.push_str("adefghijklmnop\n");
+    s
+}
+
+/// A single chain of directories `depth` levels deep, each level with a few
+/// files and a leaf directory next to the next level. The root holds a large
+/// file so that there is always something to delete for star two.
+fn transcript(depth: usize, rng: &mut Rng) -> String {
+    let mut s = String::from("$ cd /\n$ ls\ndir d0\n50000000 big.dat\n");
+    for level in 0..depth {
+        writeln!(s, "$ cd d{}\n$ ls", level).unwrap();
+        if level + 1 < depth {
+            writeln!(s, "dir d{}", level + 1).unwrap();
+        }
+        writeln!(s, "dir leaf").unwrap();
+        for file in 0..rng.between(1, 4) {
+            writeln!(s, "{} f{}.txt", rng.between(1, 200000), file).unwrap();
+        }
+        writeln!(s, "$ cd leaf\n$ ls\n{} leaf.txt\n$ cd ..", rng.between(1, 200000)).unwrap();
+    }
+    for _ in 0..depth {
+        s.push_str("$ cd ..\n");
+    }
+    s
+}
+
+fn forest(side: usize, rng: &mut Rng) -> String {
+    let mut s = String::with_capacity((side + 1) * side);
+    for _ in 0..side {
+        s.extend((0..side).map(|_| (b'0' + rng.below(10) as u8) as char));
+        s.push('\n');
+    }
+    s
+}
+
+fn motions(motions: usize, rng: &mut Rng) -> String {
+    let mut s = String::new();
+    for _ in 0..motions {
+        writeln!(s, "{} {}", rng.choose(&['L', 'R', 'U', 'D']), rng.between(1, 20)).unwrap();
+    }
+    s
+}
+
+/// Result of solving one part on a generated input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Throughput {
+    pub report: Report,
+    pub bytes: usize,
+    pub lines: usize,
+}
+
+impl Throughput {
+    pub fn bytes_per_second(&self) -> f64 {
+        self.bytes as f64 / self.report.elapsed.as_secs_f64()
+    }
+
+    pub fn lines_per_second(&self) -> f64 {
+        self.lines as f64 / self.report.elapsed.as_secs_f64()
+    }
+}
+
+impl fmt::Display for Throughput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.report)?;
+        if let Status::Solved(_) = self.report.status {
+            write!(
+                f,
+                ", {:.1} MB/s, {:.0} lines/s",
+                self.bytes_per_second() / 1e6,
+                self.lines_per_second()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Generate an input of `size` for `day` and run both parts on it. Input
+/// generation is not included in the measured time.
+pub fn run(runner: &Runner, day: u32, size: usize, seed: u64) -> Vec<Throughput> {
+    let input: Arc<str> = match generate(day, size, seed) {
+        Some(input) => input.into(),
+        None => return vec![],
+    };
+    let lines = input.lines().count();
+
+    runner::solutions_for_day(day)
+        .map(|solution| Throughput {
+            report: runner.run(solution, input.clone()),
+            bytes: input.len(),
+            lines,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::runner::{Runner, Status};
+    use crate::stress::{generate, run};
+
+    #[test]
+    fn test_generated_inputs_are_solvable() {
+        for day in 1..=9 {
+            for throughput in run(&Runner::new(), day, 30, day as u64) {
+                assert!(
+                    matches!(throughput.report.status, Status::Solved(_)),
+                    "{}",
+                    throughput
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_generation_is_seeded() {
+        assert_eq!(generate(2, 100, 1), generate(2, 100, 1));
+        assert_ne!(generate(2, 100, 1), generate(2, 100, 2));
+        assert_eq!(generate(2, 3, 7).unwrap().lines().count(), 3);
+        assert_eq!(generate(10, 3, 7), None);
+    }
+
+    #[test]
+    fn test_generated_markers_are_at_the_end() {
+        let input = generate(6, 1000, 6).unwrap();
+        let reports = run(&Runner::new(), 6, 1000, 6);
+
+        assert_eq!(input.len(), 1015);
+        assert_eq!(reports[1].report.status, Status::Solved("1014".to_string()));
+    }
+}